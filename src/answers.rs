@@ -0,0 +1,301 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::solution::Solution;
+
+/// Default location of the accepted answers
+pub fn default_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+/// Error raised when loading or saving the answers
+#[derive(Debug)]
+pub enum AnswersError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Parse { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RejectedAnswers {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    part1: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    part2: Vec<String>,
+}
+
+impl RejectedAnswers {
+    fn is_empty(&self) -> bool {
+        self.part1.is_empty() && self.part2.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+    /// Answers the website refused
+    #[serde(default, skip_serializing_if = "RejectedAnswers::is_empty")]
+    rejected: RejectedAnswers,
+}
+
+impl DayAnswers {
+    fn part_mut(&mut self, part: u8) -> &mut Option<String> {
+        match part {
+            1 => &mut self.part1,
+            2 => &mut self.part2,
+            _ => panic!("invalid part {part}"),
+        }
+    }
+
+    fn rejected_mut(&mut self, part: u8) -> &mut Vec<String> {
+        match part {
+            1 => &mut self.rejected.part1,
+            2 => &mut self.rejected.part2,
+            _ => panic!("invalid part {part}"),
+        }
+    }
+}
+
+/// Accepted answers of each day, stored in `answers.toml` as:
+///
+/// ```toml
+/// [day05]
+/// part1 = "35"
+/// part2 = "46"
+///
+/// [day05.rejected]
+/// part2 = ["45", "47"]
+/// ```
+#[derive(Debug)]
+pub struct AnswerStore {
+    path: PathBuf,
+    days: BTreeMap<String, DayAnswers>,
+}
+
+fn day_key(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+impl AnswerStore {
+    /// Loads the answers, starting from an empty store if the file doesn't exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, AnswersError> {
+        let path = path.into();
+        let days = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(|source| AnswersError::Parse {
+                path: path.clone(),
+                source,
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(source) => return Err(AnswersError::Io { path, source }),
+        };
+        Ok(Self { path, days })
+    }
+
+    pub fn save(&self) -> Result<(), AnswersError> {
+        let content = toml::to_string(&self.days).expect("answers are serializable");
+        fs::write(&self.path, content).map_err(|source| AnswersError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<&str> {
+        let answers = self.days.get(&day_key(day))?;
+        match part {
+            1 => answers.part1.as_deref(),
+            2 => answers.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Records an accepted answer, returning the one it replaces
+    pub fn record(&mut self, day: u32, part: u8, answer: &str) -> Option<String> {
+        let answers = self.days.entry(day_key(day)).or_default();
+        answers.part_mut(part).replace(answer.to_string())
+    }
+
+    /// Records an answer refused by the website
+    pub fn reject(&mut self, day: u32, part: u8, answer: &str) {
+        let rejected = self
+            .days
+            .entry(day_key(day))
+            .or_default()
+            .rejected_mut(part);
+        if !rejected.iter().any(|r| r == answer) {
+            rejected.push(answer.to_string());
+        }
+    }
+
+    pub fn is_rejected(&self, day: u32, part: u8, answer: &str) -> bool {
+        let Some(answers) = self.days.get(&day_key(day)) else {
+            return false;
+        };
+        let rejected = match part {
+            1 => &answers.rejected.part1,
+            2 => &answers.rejected.part2,
+            _ => return false,
+        };
+        rejected.iter().any(|r| r == answer)
+    }
+}
+
+/// Outcome of re-running a part against its recorded answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Fail { expected: String },
+    Unrecorded,
+    NoInput,
+    Error(String),
+}
+
+impl CheckStatus {
+    /// Whether the status should make the check fail
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail { .. } | Self::Error(_))
+    }
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Self::Unrecorded => write!(f, "unrecorded"),
+            Self::NoInput => write!(f, "no input"),
+            Self::Error(e) => write!(f, "ERROR ({})", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub day: u32,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub status: CheckStatus,
+}
+
+/// Re-runs both parts of a day and compares them with the recorded answers
+pub fn check_day(
+    solution: &dyn Solution,
+    input: Option<&str>,
+    store: &AnswerStore,
+) -> Vec<CheckResult> {
+    let day = solution.day();
+    let result = |part, answer, status| CheckResult {
+        day,
+        part,
+        answer,
+        status,
+    };
+    let Some(input) = input else {
+        return (1..=2)
+            .map(|part| result(part, None, CheckStatus::NoInput))
+            .collect();
+    };
+    let run = match solution.run(input, &[1, 2]) {
+        Ok(run) => run,
+        Err(e) => {
+            return (1..=2)
+                .map(|part| result(part, None, CheckStatus::Error(e.to_string())))
+                .collect()
+        }
+    };
+    run.parts
+        .into_iter()
+        .map(|run| {
+            let answer = match run.answer {
+                Ok(answer) => answer.to_string(),
+                Err(e) => return result(run.part, None, CheckStatus::Error(e.to_string())),
+            };
+            let status = match store.get(day, run.part) {
+                None => CheckStatus::Unrecorded,
+                Some(expected) if expected == answer => CheckStatus::Pass,
+                Some(expected) => CheckStatus::Fail {
+                    expected: expected.to_string(),
+                },
+            };
+            result(run.part, Some(answer), status)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::test::temp_dir;
+
+    #[test]
+    fn test_store_roundtrip() {
+        let path = temp_dir("answers").join("answers.toml");
+        let mut store = AnswerStore::load(&path).unwrap();
+        assert_eq!(store.get(5, 1), None);
+        assert_eq!(store.record(5, 1, "35"), None);
+        assert_eq!(store.record(5, 2, "46"), None);
+        assert_eq!(store.record(5, 1, "36"), Some("35".to_string()));
+        store.record(10, 2, "4");
+        store.reject(10, 2, "5");
+        store.reject(10, 2, "5");
+        store.save().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[day05]\npart1 = \"36\"\npart2 = \"46\"\n\n[day10]\npart2 = \"4\"\n\n\
+            [day10.rejected]\npart2 = [\"5\"]\n"
+        );
+        let store = AnswerStore::load(&path).unwrap();
+        assert_eq!(store.get(5, 1), Some("36"));
+        assert_eq!(store.get(10, 1), None);
+        assert!(store.is_rejected(10, 2, "5"));
+        assert!(!store.is_rejected(10, 1, "5"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_check_day() {
+        let path = temp_dir("answers-check").join("answers.toml");
+        let mut store = AnswerStore::load(&path).unwrap();
+        store.record(1, 1, "142");
+        store.record(1, 2, "281");
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+
+        let results = check_day(&crate::day1::Day1, Some(input), &store);
+        assert_eq!(results[0].status, CheckStatus::Pass);
+        assert_eq!(
+            results[1].status,
+            CheckStatus::Fail {
+                expected: "281".to_string()
+            }
+        );
+        assert!(results[1].status.is_failure());
+
+        let results = check_day(&crate::day1::Day1, None, &store);
+        assert!(results.iter().all(|r| r.status == CheckStatus::NoInput));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,417 @@
+use std::{
+    fmt, fs,
+    hint::black_box,
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// How long a function is run before and while being measured
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    pub warmup: Duration,
+    pub measurement: Duration,
+    pub min_iterations: usize,
+    pub max_iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(100),
+            measurement: Duration::from_secs(1),
+            min_iterations: 10,
+            max_iterations: 100_000,
+        }
+    }
+}
+
+/// Statistics of the timed iterations of a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub iterations: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+    pub min: Duration,
+}
+
+impl Stats {
+    /// Computes the statistics of a non-empty set of samples
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to compute statistics from");
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let median = if n % 2 == 0 {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        Self {
+            iterations: n,
+            mean,
+            median,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            min: sorted[0],
+        }
+    }
+}
+
+/// Runs `f` for the warmup period, then times it until the measurement period
+/// is over, within the iteration bounds of the config
+pub fn measure<T>(config: &BenchConfig, mut f: impl FnMut() -> T) -> Stats {
+    let start = Instant::now();
+    while start.elapsed() < config.warmup {
+        black_box(f());
+    }
+    let mut samples = Vec::new();
+    let start = Instant::now();
+    while samples.len() < config.min_iterations
+        || (samples.len() < config.max_iterations && start.elapsed() < config.measurement)
+    {
+        let iteration = Instant::now();
+        black_box(f());
+        samples.push(iteration.elapsed());
+    }
+    Stats::from_samples(&samples)
+}
+
+/// Timings of a part, split between parsing and solving
+#[derive(Debug, Clone, Copy)]
+pub struct PartBench {
+    pub day: u32,
+    pub part: u8,
+    pub parse: Stats,
+    pub solve: Stats,
+    /// Parsing and solving together
+    pub total: Stats,
+}
+
+/// Formats a duration with a unit suited to its magnitude
+pub struct Elapsed(pub Duration);
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        let text = match nanos {
+            0..=999 => format!("{}ns", nanos),
+            1_000..=999_999 => format!("{:.2}µs", nanos as f64 / 1e3),
+            1_000_000..=999_999_999 => format!("{:.2}ms", nanos as f64 / 1e6),
+            _ => format!("{:.2}s", nanos as f64 / 1e9),
+        };
+        f.pad(&text)
+    }
+}
+
+/// Formats a number of bytes with a binary unit suited to its magnitude
+pub struct Bytes(pub usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self.0 {
+            0..=1023 => format!("{}B", self.0),
+            1024..=0xF_FFFF => format!("{:.1}KiB", self.0 as f64 / 1024.0),
+            _ => format!("{:.1}MiB", self.0 as f64 / (1024.0 * 1024.0)),
+        };
+        f.pad(&text)
+    }
+}
+
+/// Timings of a part as saved in a report, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub day: u32,
+    pub part: u8,
+    pub iterations: usize,
+    pub parse_ns: u64,
+    pub solve_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub stddev_ns: u64,
+    pub min_ns: u64,
+}
+
+const CSV_HEADER: &str = "day,part,iterations,parse_ns,solve_ns,median_ns,mean_ns,stddev_ns,min_ns";
+
+impl From<&PartBench> for BenchRecord {
+    fn from(bench: &PartBench) -> Self {
+        let nanos = |d: Duration| d.as_nanos() as u64;
+        Self {
+            day: bench.day,
+            part: bench.part,
+            iterations: bench.total.iterations,
+            parse_ns: nanos(bench.parse.median),
+            solve_ns: nanos(bench.solve.median),
+            median_ns: nanos(bench.total.median),
+            mean_ns: nanos(bench.total.mean),
+            stddev_ns: nanos(bench.total.stddev),
+            min_ns: nanos(bench.total.min),
+        }
+    }
+}
+
+/// Format of a saved report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    /// Guesses the format of a report from its extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Self::Csv,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Error raised when reading or writing a report
+#[derive(Debug)]
+pub enum ReportError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, message: String },
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Parse { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+/// Timings of every benched part
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub results: Vec<BenchRecord>,
+}
+
+impl BenchReport {
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_string_pretty(self).expect("reports are serializable") + "\n"
+            }
+            ReportFormat::Csv => {
+                let mut csv = format!("{}\n", CSV_HEADER);
+                for r in &self.results {
+                    csv += &format!(
+                        "{},{},{},{},{},{},{},{},{}\n",
+                        r.day,
+                        r.part,
+                        r.iterations,
+                        r.parse_ns,
+                        r.solve_ns,
+                        r.median_ns,
+                        r.mean_ns,
+                        r.stddev_ns,
+                        r.min_ns
+                    );
+                }
+                csv
+            }
+        }
+    }
+
+    pub fn parse(content: &str, format: ReportFormat) -> Result<Self, String> {
+        match format {
+            ReportFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            ReportFormat::Csv => {
+                let mut lines = content.lines().enumerate();
+                match lines.next() {
+                    Some((_, header)) if header == CSV_HEADER => {}
+                    _ => return Err(format!("expected the header `{}`", CSV_HEADER)),
+                }
+                let results = lines
+                    .filter(|(_, line)| !line.is_empty())
+                    .map(|(i, line)| {
+                        let error = || format!("line {}: expected 9 integer columns", i + 1);
+                        let values: Vec<u64> = line
+                            .split(',')
+                            .map(|v| v.trim().parse().map_err(|_| error()))
+                            .collect::<Result<_, _>>()?;
+                        let [day, part, iterations, parse_ns, solve_ns, median_ns, mean_ns, stddev_ns, min_ns] =
+                            values[..]
+                        else {
+                            return Err(error());
+                        };
+                        Ok(BenchRecord {
+                            day: day as u32,
+                            part: part as u8,
+                            iterations: iterations as usize,
+                            parse_ns,
+                            solve_ns,
+                            median_ns,
+                            mean_ns,
+                            stddev_ns,
+                            min_ns,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Self { results })
+            }
+        }
+    }
+
+    /// Loads a report, in the format given by its extension
+    pub fn load(path: &Path) -> Result<Self, ReportError> {
+        let content = fs::read_to_string(path).map_err(|source| ReportError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&content, ReportFormat::from_path(path)).map_err(|message| ReportError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    pub fn save(&self, path: &Path, format: ReportFormat) -> Result<(), ReportError> {
+        fs::write(path, self.render(format)).map_err(|source| ReportError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn get(&self, day: u32, part: u8) -> Option<&BenchRecord> {
+        self.results.iter().find(|r| r.day == day && r.part == part)
+    }
+}
+
+/// Median run time of a part compared with a baseline report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub day: u32,
+    pub part: u8,
+    pub baseline_ns: Option<u64>,
+    pub current_ns: u64,
+}
+
+impl Comparison {
+    /// Relative change of the median, e.g. `0.25` when 25% slower
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline_ns.filter(|b| *b > 0)?;
+        Some(self.current_ns as f64 / baseline as f64 - 1.0)
+    }
+
+    /// Whether the part got slower than allowed by the threshold, e.g. `0.1`
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold)
+    }
+}
+
+/// Compares each part of a report with the same part in a baseline
+pub fn compare(baseline: &BenchReport, current: &BenchReport) -> Vec<Comparison> {
+    current
+        .results
+        .iter()
+        .map(|r| Comparison {
+            day: r.day,
+            part: r.part,
+            baseline_ns: baseline.get(r.day, r.part).map(|b| b.median_ns),
+            current_ns: r.median_ns,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2, 10].map(Duration::from_micros);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.iterations, 5);
+        assert_eq!(stats.mean, Duration::from_micros(4));
+        assert_eq!(stats.median, Duration::from_micros(3));
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.stddev.as_micros(), 3);
+
+        let stats = Stats::from_samples(&samples[..4]);
+        assert_eq!(stats.median, Duration::from_nanos(2500));
+    }
+
+    #[test]
+    fn test_bench_and_run() {
+        let config = BenchConfig {
+            warmup: Duration::ZERO,
+            measurement: Duration::ZERO,
+            min_iterations: 3,
+            max_iterations: 3,
+        };
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        let solution: &dyn Solution = &crate::day1::Day1;
+        let bench = solution.bench(input, 1, &config).unwrap();
+        assert_eq!((bench.day, bench.part), (1, 1));
+        assert_eq!(bench.solve.iterations, 3);
+        let run = solution.run(input, &[1, 2]).unwrap();
+        assert_eq!(run.parts[0].answer, Ok(Answer::Unsigned(142)));
+        assert_eq!(run.parts[1].part, 2);
+        assert_eq!(Elapsed(Duration::from_micros(1500)).to_string(), "1.50ms");
+        assert_eq!(Bytes(1536).to_string(), "1.5KiB");
+    }
+
+    fn record(day: u32, part: u8, median_ns: u64) -> BenchRecord {
+        BenchRecord {
+            day,
+            part,
+            iterations: 10,
+            parse_ns: 10,
+            solve_ns: median_ns - 10,
+            median_ns,
+            mean_ns: median_ns + 5,
+            stddev_ns: 3,
+            min_ns: median_ns - 5,
+        }
+    }
+
+    #[test]
+    fn test_report_roundtrip() {
+        let report = BenchReport {
+            results: vec![record(3, 1, 1000), record(5, 2, 20000)],
+        };
+        for format in [ReportFormat::Json, ReportFormat::Csv] {
+            let rendered = report.render(format);
+            assert_eq!(BenchReport::parse(&rendered, format), Ok(report.clone()));
+        }
+        assert_eq!(
+            report.render(ReportFormat::Csv).lines().nth(1),
+            Some("3,1,10,10,990,1000,1005,3,995")
+        );
+        let error = BenchReport::parse("day,part\n", ReportFormat::Csv).unwrap_err();
+        assert!(error.starts_with("expected the header"));
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = BenchReport {
+            results: vec![record(3, 1, 1000), record(3, 2, 1000)],
+        };
+        let current = BenchReport {
+            results: vec![record(3, 1, 1050), record(3, 2, 1200), record(4, 1, 100)],
+        };
+        let comparisons = compare(&baseline, &current);
+        let regressed = comparisons
+            .iter()
+            .filter(|c| c.regressed(0.1))
+            .map(|c| (c.day, c.part))
+            .collect::<Vec<_>>();
+        assert_eq!(regressed, vec![(3, 2)]);
+        assert_eq!(comparisons[2].change(), None);
+    }
+}
@@ -0,0 +1,515 @@
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    time::Duration,
+};
+
+use aoc23::{
+    answers::{self, AnswerStore},
+    bench::{self, BenchConfig, BenchRecord, BenchReport, Bytes, Elapsed, ReportFormat},
+    client::{self, Client, FetchStatus},
+    day10, day3, day8, samples, scaffold,
+    solution::{Day, Solution},
+    submit,
+    utils::{self, InputSource},
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: aoc23::memory::CountingAllocator = aoc23::memory::CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solution of a day, or of every day with `all`
+    Run {
+        /// Day number (1-25) or `all`
+        day: DaySelection,
+        /// Only run this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Read the input from this file, or from stdin with `-`
+        #[arg(long)]
+        input: Option<String>,
+    },
+    /// Time the solution of a day, or of every day
+    Bench(BenchArgs),
+    /// Download the input and puzzle of a day, unless already downloaded
+    Fetch {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Create the module of a new day from `template.rs`
+    New {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Extract the examples of a downloaded puzzle into sample fixtures
+    Samples {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Run a day and record its answers as accepted in `answers.toml`
+    Record {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        /// Only record this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+    },
+    /// Run a part and submit its answer to the website
+    Submit {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+    /// Render the input of a day as a picture
+    Viz(VizArgs),
+    /// Check the solutions against the answers recorded in `answers.toml`
+    Check {
+        /// Only check this day
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: Option<u32>,
+    },
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Only bench this day
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: Option<u32>,
+    /// Only bench this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Write the JSON or CSV report to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Compare the median times with a saved JSON or CSV report
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// Slowdown, in percent, above which a part counts as regressed
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+#[derive(Args)]
+struct VizArgs {
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: u32,
+    /// Write a Graphviz graph, for the days whose input is a graph
+    #[arg(long, conflicts_with = "output")]
+    dot: bool,
+    /// Print plain text, without ANSI colours
+    #[arg(long)]
+    no_color: bool,
+    /// Save a picture to this `.svg` or `.png` file instead
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Read the input from this file, or from stdin with `-`
+    #[arg(long)]
+    input: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DaySelection {
+    All,
+    Day(u32),
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Self::All);
+        }
+        match s.parse() {
+            Ok(day) if (1..=25).contains(&day) => Ok(Self::Day(day)),
+            _ => Err(format!(
+                "expected a day between 1 and 25 or `all`, got `{s}`"
+            )),
+        }
+    }
+}
+
+fn run_day(solution: &dyn Solution, part: Option<u8>, source: InputSource) -> Result<(), String> {
+    let day = solution.day();
+    let input = source.read().map_err(|e| e.to_string())?;
+    let run = solution
+        .run(&input, &parts(part))
+        .map_err(|e| e.to_string())?;
+    println!("Day {day}: {}", solution.title());
+    for part in run.parts {
+        let answer = part.answer.map_err(|e| e.to_string())?;
+        println!("  Part {}: {answer}", part.part);
+    }
+    Ok(())
+}
+
+/// Runs every day with an input, summing up their timings in a table
+fn run_all(part: Option<u8>) -> Result<(), String> {
+    println!(
+        "{:>3}  {:>4}  {:>16}  {:>10}  {:>10}  {:>10}",
+        "day", "part", "answer", "parse", "solve", "peak"
+    );
+    let (mut parse, mut solve, mut peak) = (Duration::ZERO, Duration::ZERO, None);
+    let mut errors = 0;
+    for solution in aoc23::DAYS {
+        let day = solution.day();
+        let Ok(input) = InputSource::Day(day).read() else {
+            println!("{:>3}  {:>4}  {:>16}", day, "-", "no input");
+            continue;
+        };
+        let run = match solution.run(&input, &parts(part)) {
+            Ok(run) => run,
+            Err(e) => {
+                errors += 1;
+                println!("{:>3}  {:>4}  ERROR ({})", day, "-", e);
+                continue;
+            }
+        };
+        parse += run.parse;
+        // The input is parsed once for both parts, its time is on the first row
+        let mut parse_time = Some(run.parse);
+        for part in run.parts {
+            let answer = match part.answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    errors += 1;
+                    println!("{:>3}  {:>4}  ERROR ({})", day, part.part, e);
+                    continue;
+                }
+            };
+            solve += part.solve;
+            peak = peak.max(part.peak_bytes);
+            println!(
+                "{:>3}  {:>4}  {:>16}  {:>10}  {:>10}  {:>10}",
+                day,
+                part.part,
+                answer.to_string(),
+                parse_time
+                    .take()
+                    .map_or(String::new(), |t| Elapsed(t).to_string()),
+                Elapsed(part.solve),
+                part.peak_bytes
+                    .map_or("-".to_string(), |b| Bytes(b).to_string()),
+            );
+        }
+    }
+    println!(
+        "{:>9}  {:>16}  {:>10}  {:>10}  {:>10}",
+        "total",
+        "",
+        Elapsed(parse),
+        Elapsed(solve),
+        peak.map_or("-".to_string(), |b| Bytes(b).to_string()),
+    );
+    match errors {
+        0 => Ok(()),
+        n => Err(format!("{n} part(s) failed")),
+    }
+}
+
+fn bench(args: BenchArgs) -> Result<(), String> {
+    let report_format = match args.format {
+        Format::Table if args.output.is_some() => {
+            return Err("--output requires --format json or csv".to_string())
+        }
+        Format::Table => None,
+        Format::Json => Some(ReportFormat::Json),
+        Format::Csv => Some(ReportFormat::Csv),
+    };
+    let baseline = match &args.baseline {
+        Some(path) => Some(BenchReport::load(path).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let solutions = match args.day {
+        Some(day) => vec![get_day(day)?],
+        None => aoc23::DAYS.to_vec(),
+    };
+    let config = BenchConfig::default();
+    if report_format.is_none() {
+        println!(
+            "{:>3}  {:>4}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>6}",
+            "day", "part", "parse", "solve", "median", "mean", "stddev", "min", "iters"
+        );
+    }
+    let mut report = BenchReport::default();
+    for solution in solutions {
+        let input = match InputSource::Day(solution.day()).read() {
+            Ok(input) => input,
+            Err(e) if args.day.is_some() => return Err(e.to_string()),
+            Err(_) => continue,
+        };
+        for part in parts(args.part) {
+            let bench = solution
+                .bench(&input, part, &config)
+                .map_err(|e| e.to_string())?;
+            if report_format.is_none() {
+                println!(
+                    "{:>3}  {:>4}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>6}",
+                    bench.day,
+                    bench.part,
+                    Elapsed(bench.parse.median),
+                    Elapsed(bench.solve.median),
+                    Elapsed(bench.total.median),
+                    Elapsed(bench.total.mean),
+                    Elapsed(bench.total.stddev),
+                    Elapsed(bench.total.min),
+                    bench.total.iterations,
+                );
+            }
+            report.results.push(BenchRecord::from(&bench));
+        }
+    }
+
+    let report_on_stdout = match (report_format, &args.output) {
+        (Some(format), Some(path)) => {
+            report.save(path, format).map_err(|e| e.to_string())?;
+            false
+        }
+        (Some(format), None) => {
+            print!("{}", report.render(format));
+            true
+        }
+        (None, _) => false,
+    };
+    let Some(baseline) = baseline else {
+        return Ok(());
+    };
+    // Keep stdout parseable when the report is written to it
+    let mut comparison = format!(
+        "\n{:>3}  {:>4}  {:>10}  {:>10}  {:>8}\n",
+        "day", "part", "baseline", "current", "change"
+    );
+    let mut regressions = 0;
+    for c in bench::compare(&baseline, &report) {
+        let regressed = c.regressed(args.threshold / 100.0);
+        if regressed {
+            regressions += 1;
+        }
+        let baseline = c
+            .baseline_ns
+            .map(|b| Elapsed(Duration::from_nanos(b)).to_string());
+        let change = c.change().map(|change| format!("{:+.1}%", change * 100.0));
+        comparison += &format!(
+            "{:>3}  {:>4}  {:>10}  {:>10}  {:>8}{}\n",
+            c.day,
+            c.part,
+            baseline.as_deref().unwrap_or("-"),
+            Elapsed(Duration::from_nanos(c.current_ns)),
+            change.as_deref().unwrap_or("-"),
+            if regressed { "  REGRESSED" } else { "" }
+        );
+    }
+    if report_on_stdout {
+        eprint!("{}", comparison);
+    } else {
+        print!("{}", comparison);
+    }
+    match regressions {
+        0 => Ok(()),
+        n => Err(format!(
+            "{n} part(s) regressed by more than {}%",
+            args.threshold
+        )),
+    }
+}
+
+fn fetch(day: u32) -> Result<(), String> {
+    let client = Client::from_env().map_err(|e| e.to_string())?;
+    let report = client::fetch_day(&client, day, &utils::input_dir(), &client::puzzle_dir())
+        .map_err(|e| e.to_string())?;
+    for (path, status) in [report.input, report.puzzle] {
+        let status = match status {
+            FetchStatus::Downloaded => "downloaded",
+            FetchStatus::Cached => "already downloaded",
+        };
+        println!("{}: {}", path.display(), status);
+    }
+    Ok(())
+}
+
+fn new(day: u32) -> Result<(), String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let scaffold = scaffold::new_day(root, &utils::input_dir(), day).map_err(|e| e.to_string())?;
+    println!("Created {}", scaffold.module.display());
+    println!("Registered day {day} in {}", scaffold.lib.display());
+    if let Some(input) = scaffold.input {
+        println!("Created an empty input at {}", input.display());
+    }
+    for sample in scaffold.samples {
+        println!("Extracted {}", sample.display());
+    }
+    Ok(())
+}
+
+fn extract_samples(day: u32) -> Result<(), String> {
+    let puzzle_path = client::puzzle_path(&client::puzzle_dir(), day);
+    let puzzle = fs::read_to_string(&puzzle_path)
+        .map_err(|e| format!("unable to read {}: {e}", puzzle_path.display()))?;
+    let samples = samples::extract_samples(&puzzle);
+    if samples.is_empty() {
+        return Err(format!("no example found in {}", puzzle_path.display()));
+    }
+    let paths = samples::write_samples(&samples::sample_dir(&utils::input_dir()), day, &samples)
+        .map_err(|e| e.to_string())?;
+    for path in paths {
+        println!("Extracted {}", path.display());
+    }
+    Ok(())
+}
+
+fn viz(args: VizArgs) -> Result<(), String> {
+    let input = InputSource::from_arg(args.day, args.input.as_deref())
+        .read()
+        .map_err(|e| e.to_string())?;
+    if let Some(path) = &args.output {
+        let picture = match args.day {
+            3 => day3::picture(&day3::Day3.parse(&input).map_err(|e| e.to_string())?),
+            10 => day10::picture(&day10::Day10.parse(&input).map_err(|e| e.to_string())?),
+            day => return Err(format!("day {day} can't be saved as a picture")),
+        };
+        return picture.save(path).map_err(|e| e.to_string());
+    }
+    let output = match args.day {
+        3 => return Err("day 3 can only be saved as a picture with --output".to_string()),
+        8 if args.dot => {
+            let network = day8::Day8.parse(&input).map_err(|e| e.to_string())?;
+            day8::to_dot(&network)
+        }
+        8 => return Err("day 8 can only be rendered with --dot".to_string()),
+        10 if args.dot => return Err("day 10 can't be rendered with --dot".to_string()),
+        10 => {
+            let maze = day10::Day10.parse(&input).map_err(|e| e.to_string())?;
+            day10::render(&maze, !args.no_color && io::stdout().is_terminal())
+        }
+        day => return Err(format!("day {day} has no visualisation")),
+    };
+    print!("{output}");
+    Ok(())
+}
+
+fn get_day(day: u32) -> Result<&'static dyn Solution, String> {
+    aoc23::get_day(day).ok_or_else(|| format!("day {day} is not solved yet"))
+}
+
+fn parts(part: Option<u8>) -> Vec<u8> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    }
+}
+
+fn record(day: u32, part: Option<u8>) -> Result<(), String> {
+    let solution = get_day(day)?;
+    let input = InputSource::Day(day).read().map_err(|e| e.to_string())?;
+    let mut store = AnswerStore::load(answers::default_path()).map_err(|e| e.to_string())?;
+    for part in parts(part) {
+        let answer = solution
+            .part(part, &input)
+            .map_err(|e| e.to_string())?
+            .to_string();
+        match store.record(day, part, &answer) {
+            Some(previous) if previous != answer => {
+                println!("Day {day} part {part}: {answer} (was {previous})")
+            }
+            _ => println!("Day {day} part {part}: {answer}"),
+        }
+    }
+    store.save().map_err(|e| e.to_string())
+}
+
+fn submit(day: u32, part: u8) -> Result<(), String> {
+    let solution = get_day(day)?;
+    let input = InputSource::Day(day).read().map_err(|e| e.to_string())?;
+    let answer = solution
+        .part(part, &input)
+        .map_err(|e| e.to_string())?
+        .to_string();
+    let client = Client::from_env().map_err(|e| e.to_string())?;
+    let mut store = AnswerStore::load(answers::default_path()).map_err(|e| e.to_string())?;
+    let outcome =
+        submit::submit(&client, &mut store, day, part, &answer).map_err(|e| e.to_string())?;
+    store.save().map_err(|e| e.to_string())?;
+    println!("Day {day} part {part}: {answer}: {outcome}");
+    Ok(())
+}
+
+fn check(day: Option<u32>) -> Result<(), String> {
+    let solutions = match day {
+        Some(day) => vec![get_day(day)?],
+        None => aoc23::DAYS.to_vec(),
+    };
+    let store = AnswerStore::load(answers::default_path()).map_err(|e| e.to_string())?;
+    println!("{:>3}  {:>4}  {:>16}  status", "day", "part", "answer");
+    let mut failures = 0;
+    for solution in solutions {
+        let input = InputSource::Day(solution.day()).read().ok();
+        for result in answers::check_day(solution, input.as_deref(), &store) {
+            if result.status.is_failure() {
+                failures += 1;
+            }
+            println!(
+                "{:>3}  {:>4}  {:>16}  {}",
+                result.day,
+                result.part,
+                result.answer.as_deref().unwrap_or("-"),
+                result.status
+            );
+        }
+    }
+    match failures {
+        0 => Ok(()),
+        n => Err(format!("{n} part(s) don't match their recorded answer")),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run { day, part, input } => match day {
+            DaySelection::All if input.is_some() => {
+                Err("--input can only be used with a single day".to_string())
+            }
+            DaySelection::All => run_all(part),
+            DaySelection::Day(day) => get_day(day)
+                .and_then(|s| run_day(s, part, InputSource::from_arg(day, input.as_deref()))),
+        },
+        Command::Bench(args) => bench(args),
+        Command::Fetch { day } => fetch(day),
+        Command::New { day } => new(day),
+        Command::Samples { day } => extract_samples(day),
+        Command::Record { day, part } => record(day, part),
+        Command::Submit { day, part } => submit(day, part),
+        Command::Viz(args) => viz(args),
+        Command::Check { day } => check(day),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,557 @@
+use std::{
+    cell::Cell,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const YEAR: u32 = 2023;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Environment variable holding the session cookie of the website
+pub const SESSION_VAR: &str = "ADVENT_OF_CODE_SESSION";
+/// Environment variable overriding the website URL, e.g. to use a local server
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Identifies the tool to the website maintainers, as they request
+const USER_AGENT: &str = "github.com/art049/AoC23 by arthur.pastel@gmail.com";
+/// Minimum delay between two requests to the website
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Error raised when talking to the website
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    Status { url: String, status: u16 },
+    Transport(String),
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSession => write!(
+                f,
+                "no session token, set {} in the environment or in .env",
+                SESSION_VAR
+            ),
+            Self::Status { url, status } => write!(f, "{} returned HTTP {}", url, status),
+            Self::Transport(e) => write!(f, "request failed: {}", e),
+            Self::Io { path, source } => {
+                write!(f, "unable to write {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::MissingSession | Self::Status { .. } | Self::Transport(_) => None,
+        }
+    }
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, response) => Self::Status {
+                url: response.get_url().to_string(),
+                status,
+            },
+            ureq::Error::Transport(e) => Self::Transport(e.to_string()),
+        }
+    }
+}
+
+/// Reads a variable from the environment, falling back to the `.env` file
+pub fn env_var(name: &str) -> Option<String> {
+    if let Ok(value) = env::var(name) {
+        return Some(value);
+    }
+    let dotenv = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(".env")).ok()?;
+    dotenv.lines().find_map(|line| {
+        let line = line.trim();
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=')?;
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        (key.trim() == name && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Blocking client of the Advent of Code website
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+    min_interval: Duration,
+    last_request: Cell<Option<SystemTime>>,
+    /// File keeping the time of the last request, to throttle across runs
+    stamp: Option<PathBuf>,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: session.into(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+            min_interval: DEFAULT_MIN_INTERVAL,
+            last_request: Cell::new(None),
+            stamp: None,
+        }
+    }
+
+    /// Client configured from `ADVENT_OF_CODE_SESSION` and `AOC_BASE_URL`
+    pub fn from_env() -> Result<Self, ClientError> {
+        let session = env_var(SESSION_VAR).ok_or(ClientError::MissingSession)?;
+        let base_url = env_var(BASE_URL_VAR).unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        Ok(Self::new(base_url, session).with_stamp(puzzle_dir().join(".last-request")))
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Keeps the time of the last request in `path`, so that the rate limit
+    /// holds across runs
+    pub fn with_stamp(mut self, path: PathBuf) -> Self {
+        self.stamp = Some(path);
+        self
+    }
+
+    /// Time of the last request, from this client or from the stamp file
+    fn last_request(&self) -> Option<SystemTime> {
+        let stamped = self.stamp.as_ref().and_then(|path| {
+            let millis = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+            Some(UNIX_EPOCH + Duration::from_millis(millis))
+        });
+        self.last_request.get().max(stamped)
+    }
+
+    fn day_url(&self, day: u32) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    /// Waits until the next request is allowed by the rate limit
+    fn throttle(&self) {
+        if let Some(last) = self.last_request() {
+            // A stamp in the future, e.g. after a clock change, counts as now
+            let elapsed = last.elapsed().unwrap_or(Duration::ZERO);
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        let now = SystemTime::now();
+        self.last_request.set(Some(now));
+        if let Some(path) = &self.stamp {
+            // The stamp is best effort, a failure to write it must not block requests
+            let millis = now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let _ = path.parent().map(fs::create_dir_all);
+            let _ = fs::write(path, millis.to_string());
+        }
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        self.throttle();
+        self.agent
+            .request(method, url)
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn get(&self, url: &str) -> Result<String, ClientError> {
+        let response = self.request("GET", url).call()?;
+        response
+            .into_string()
+            .map_err(|e| ClientError::Transport(e.to_string()))
+    }
+
+    pub fn fetch_input(&self, day: u32) -> Result<String, ClientError> {
+        self.get(&format!("{}/input", self.day_url(day)))
+    }
+
+    /// Puzzle description, as the HTML of its `<article>` sections
+    pub fn fetch_puzzle(&self, day: u32) -> Result<String, ClientError> {
+        let page = self.get(&self.day_url(day))?;
+        Ok(extract_articles(&page))
+    }
+
+    pub fn submit_answer(
+        &self,
+        day: u32,
+        part: u8,
+        answer: &str,
+    ) -> Result<SubmitOutcome, ClientError> {
+        let url = format!("{}/answer", self.day_url(day));
+        let response = self
+            .request("POST", &url)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?;
+        let page = response
+            .into_string()
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(classify_response(&page))
+    }
+}
+
+/// Response of the website to a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// An answer was submitted too recently, the wait time is given when known
+    RateLimited(Option<Duration>),
+    AlreadySolved,
+    /// Response that couldn't be classified, with its text
+    Unknown(String),
+}
+
+impl fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct answer"),
+            Self::TooHigh => write!(f, "wrong answer, too high"),
+            Self::TooLow => write!(f, "wrong answer, too low"),
+            Self::Wrong => write!(f, "wrong answer"),
+            Self::RateLimited(Some(wait)) => {
+                write!(f, "answered too recently, wait {}s", wait.as_secs())
+            }
+            Self::RateLimited(None) => write!(f, "answered too recently"),
+            Self::AlreadySolved => write!(f, "part already solved"),
+            Self::Unknown(text) => write!(f, "unexpected response: {}", text),
+        }
+    }
+}
+
+/// Parses the wait time of `You have 4m 36s left to wait`
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (before, _) = text.split_once(" left to wait")?;
+    let (_, wait) = before.rsplit_once("You have ")?;
+    let mut seconds = 0;
+    for component in wait.split_whitespace() {
+        let (value, unit) = component.split_at(component.find(|c: char| !c.is_ascii_digit())?);
+        let value: u64 = value.parse().ok()?;
+        seconds += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// Classifies the page returned after submitting an answer
+pub fn classify_response(page: &str) -> SubmitOutcome {
+    let article = extract_articles(page);
+    let text = if article.is_empty() { page } else { &article };
+    if text.contains("That's the right answer") {
+        SubmitOutcome::Correct
+    } else if text.contains("your answer is too high") {
+        SubmitOutcome::TooHigh
+    } else if text.contains("your answer is too low") {
+        SubmitOutcome::TooLow
+    } else if text.contains("That's not the right answer") {
+        SubmitOutcome::Wrong
+    } else if text.contains("You gave an answer too recently") {
+        SubmitOutcome::RateLimited(parse_wait(text))
+    } else if text.contains("You don't seem to be solving the right level") {
+        SubmitOutcome::AlreadySolved
+    } else {
+        SubmitOutcome::Unknown(text.trim().to_string())
+    }
+}
+
+/// Keeps the `<article>` elements of a puzzle page, one per part
+fn extract_articles(page: &str) -> String {
+    let mut articles = Vec::new();
+    let mut rest = page;
+    while let Some(start) = rest.find("<article") {
+        let Some(len) = rest[start..].find("</article>") else {
+            break;
+        };
+        let end = start + len + "</article>".len();
+        articles.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    articles.join("\n\n")
+}
+
+/// Directory where puzzle descriptions are stored
+pub fn puzzle_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("puzzles")
+}
+
+pub fn puzzle_path(puzzle_dir: &Path, day: u32) -> PathBuf {
+    puzzle_dir.join(format!("day{:02}.html", day))
+}
+
+/// Outcome of a fetch for each file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchStatus {
+    Downloaded,
+    Cached,
+}
+
+#[derive(Debug)]
+pub struct FetchReport {
+    pub input: (PathBuf, FetchStatus),
+    pub puzzle: (PathBuf, FetchStatus),
+}
+
+/// Downloads the input and puzzle of a day, unless they are already on disk
+pub fn fetch_day(
+    client: &Client,
+    day: u32,
+    input_dir: &Path,
+    puzzle_dir: &Path,
+) -> Result<FetchReport, ClientError> {
+    let input_path = input_dir.join(format!("day{:02}.txt", day));
+    let input = cache(&input_path, || client.fetch_input(day))?;
+    let puzzle_path = puzzle_path(puzzle_dir, day);
+    let puzzle = cache(&puzzle_path, || client.fetch_puzzle(day))?;
+    Ok(FetchReport {
+        input: (input_path, input),
+        puzzle: (puzzle_path, puzzle),
+    })
+}
+
+/// Downloads a file unless it is on disk, empty files standing for missing
+/// ones like the input placeholders created by `aoc new`
+fn cache(
+    path: &Path,
+    download: impl FnOnce() -> Result<String, ClientError>,
+) -> Result<FetchStatus, ClientError> {
+    if fs::metadata(path).is_ok_and(|m| m.len() > 0) {
+        return Ok(FetchStatus::Cached);
+    }
+    let content = download()?;
+    let io_error = |source| ClientError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, content).map_err(io_error)?;
+    Ok(FetchStatus::Downloaded)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+    use crate::utils::test::temp_dir;
+
+    /// Request received by the [`StubServer`]
+    #[derive(Debug, Clone)]
+    pub struct StubRequest {
+        pub method: String,
+        pub path: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    impl StubRequest {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Minimal HTTP server answering every request with `respond`
+    pub struct StubServer {
+        pub url: String,
+        pub requests: Arc<Mutex<Vec<StubRequest>>>,
+    }
+
+    impl StubServer {
+        pub fn start(respond: impl Fn(&StubRequest) -> (u16, String) + Send + 'static) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let received = requests.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+                    let mut headers = Vec::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        let Some((key, value)) = line.trim_end().split_once(": ") else {
+                            break;
+                        };
+                        headers.push((key.to_string(), value.to_string()));
+                    }
+                    let mut request = StubRequest {
+                        method,
+                        path,
+                        headers,
+                        body: String::new(),
+                    };
+                    let length = request
+                        .header("Content-Length")
+                        .and_then(|l| l.parse().ok())
+                        .unwrap_or(0);
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    request.body = String::from_utf8(body).unwrap();
+                    let (status, body) = respond(&request);
+                    received.lock().unwrap().push(request);
+                    write!(
+                        stream,
+                        "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    )
+                    .unwrap();
+                }
+            });
+            Self { url, requests }
+        }
+    }
+
+    #[test]
+    fn test_fetch_day_downloads_once() {
+        let server = StubServer::start(|request| match request.path.as_str() {
+            "/2023/day/5/input" => (200, "seeds: 79 14 55 13\n".to_string()),
+            "/2023/day/5" => (
+                200,
+                "<html><main><article class=\"day-desc\"><h2>Day 5</h2></article></main></html>"
+                    .to_string(),
+            ),
+            _ => (404, String::new()),
+        });
+        let client = Client::new(server.url.as_str(), "token").with_min_interval(Duration::ZERO);
+        let dir = temp_dir("fetch");
+        let (inputs, puzzles) = (dir.join("inputs"), dir.join("puzzles"));
+
+        let report = fetch_day(&client, 5, &inputs, &puzzles).unwrap();
+        assert_eq!(report.input.1, FetchStatus::Downloaded);
+        assert_eq!(report.puzzle.1, FetchStatus::Downloaded);
+        assert_eq!(
+            fs::read_to_string(inputs.join("day05.txt")).unwrap(),
+            "seeds: 79 14 55 13\n"
+        );
+        assert_eq!(
+            fs::read_to_string(puzzles.join("day05.html")).unwrap(),
+            "<article class=\"day-desc\"><h2>Day 5</h2></article>"
+        );
+
+        let report = fetch_day(&client, 5, &inputs, &puzzles).unwrap();
+        assert_eq!(report.input.1, FetchStatus::Cached);
+        assert_eq!(report.puzzle.1, FetchStatus::Cached);
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        for request in requests.iter() {
+            assert_eq!(request.header("Cookie"), Some("session=token"));
+            assert_eq!(request.header("User-Agent"), Some(USER_AGENT));
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_throttle_across_clients() {
+        let dir = temp_dir("throttle");
+        let stamp = dir.join("puzzles/.last-request");
+        let client = |interval| {
+            Client::new("http://localhost", "token")
+                .with_min_interval(interval)
+                .with_stamp(stamp.clone())
+        };
+        client(Duration::ZERO).throttle();
+        let first = client(Duration::ZERO).last_request().unwrap();
+
+        // A fresh client waits for the interval since the stamped request
+        let interval = Duration::from_millis(200);
+        client(interval).throttle();
+        let second = client(interval).last_request().unwrap();
+        assert!(second.duration_since(first).unwrap() >= interval);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_day_reports_io_errors() {
+        let server = StubServer::start(|_| (200, "input\n".to_string()));
+        let client = Client::new(server.url.as_str(), "token").with_min_interval(Duration::ZERO);
+        let dir = temp_dir("fetch-io");
+        // The input directory can't be created over a file
+        let inputs = dir.join("inputs");
+        fs::write(&inputs, "").unwrap();
+        let error = fetch_day(&client, 5, &inputs, &dir).unwrap_err();
+        assert!(matches!(error, ClientError::Io { ref path, .. } if path.starts_with(&inputs)));
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(source.downcast_ref::<io::Error>().is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_classify_response() {
+        let page = |text: &str| format!("<main><article><p>{}</p></article></main>", text);
+        let cases = [
+            (
+                "That's the right answer! You are one gold star closer.",
+                SubmitOutcome::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high.",
+                SubmitOutcome::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                SubmitOutcome::TooLow,
+            ),
+            (
+                "That's not the right answer. If you're stuck...",
+                SubmitOutcome::Wrong,
+            ),
+            (
+                "You gave an answer too recently. You have 4m 36s left to wait.",
+                SubmitOutcome::RateLimited(Some(Duration::from_secs(276))),
+            ),
+            (
+                "You gave an answer too recently. You have 36s left to wait.",
+                SubmitOutcome::RateLimited(Some(Duration::from_secs(36))),
+            ),
+            (
+                "You don't seem to be solving the right level. Did you already complete it?",
+                SubmitOutcome::AlreadySolved,
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(classify_response(&page(text)), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_fetch_day_reports_http_errors() {
+        let server = StubServer::start(|_| (404, String::new()));
+        let client = Client::new(server.url.as_str(), "token").with_min_interval(Duration::ZERO);
+        let dir = temp_dir("fetch-error");
+        let error = fetch_day(&client, 30, &dir, &dir).unwrap_err();
+        assert!(matches!(error, ClientError::Status { status: 404, .. }));
+        assert!(!dir.join("day30.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
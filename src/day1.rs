@@ -1,18 +1,28 @@
 use int_enum::IntEnum;
-use itertools::Itertools;
 use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
-pub fn part1(input: &str) -> u32 {
-    let lines: Vec<&str> = input.lines().collect();
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, Line, ParseError},
+};
+
+const DAY: u32 = 1;
+
+/// Number made of the first and last digits of a line
+fn calibration_value(line: &Line<'_>, text: &str) -> Result<u32, ParseError> {
+    let mut digits_chars = text.chars().filter(|c| c.is_ascii_digit());
+    let first = digits_chars
+        .next()
+        .ok_or_else(|| line.error("at least one digit"))?;
+    let last = digits_chars.next_back().unwrap_or(first);
+    let number: String = [first, last].iter().collect();
+    Ok(number.parse::<u32>().unwrap())
+}
+
+fn part1(lines: &[Line<'_>]) -> Result<u32, ParseError> {
     lines
         .iter()
-        .map(|l| {
-            let digits_chars = l.chars().filter(|c| c.is_ascii_digit());
-            let first = digits_chars.clone().next().unwrap();
-            let last = digits_chars.last().unwrap();
-            let number: String = [first, last].iter().collect();
-            number.parse::<u32>().unwrap()
-        })
+        .map(|line| calibration_value(line, line.text))
         .sum()
 }
 
@@ -31,42 +41,60 @@ enum Digit {
     Nine = 9,
 }
 
-pub fn part2(input: &str) -> u32 {
-    let mut lines: Vec<&str> = input.lines().collect();
-    let lines = lines
+fn part2(lines: &[Line<'_>]) -> Result<u32, ParseError> {
+    lines
         .iter()
         .map(|line| {
-            let mut line = line.to_string();
-            let mut first_str_digit = (line.len(), None);
+            let mut text = line.text.to_string();
+            let mut first_str_digit = (text.len(), None);
             let mut last_str_digit = (0, None);
             for digit in Digit::iter() {
-                if let Some(pos) = line.find(digit.to_string().as_str()) {
+                if let Some(pos) = text.find(digit.to_string().as_str()) {
                     if pos < first_str_digit.0 {
                         first_str_digit = (pos, Some(digit))
                     }
                 }
-                if let Some(pos) = line.rfind(digit.to_string().as_str()) {
+                if let Some(pos) = text.rfind(digit.to_string().as_str()) {
                     if pos > last_str_digit.0 {
                         last_str_digit = (pos, Some(digit))
                     }
                 }
             }
             if let (pos, Some(digit)) = first_str_digit {
-                line.insert_str(pos, digit.int_value().to_string().as_str());
+                text.insert_str(pos, digit.int_value().to_string().as_str());
             }
             if let (pos, Some(digit)) = last_str_digit {
-                line.insert_str(pos + 1, digit.int_value().to_string().as_str());
+                text.insert_str(pos + 1, digit.int_value().to_string().as_str());
             }
-            line
+            calibration_value(line, &text)
         })
-        .collect_vec();
-    part1(&lines.join("\n"))
+        .sum()
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day1;
+
+impl Day for Day1 {
+    type Parsed<'a> = Vec<Line<'a>>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        Ok(utils::lines(DAY, input).collect())
+    }
+
+    fn part1(&self, lines: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(lines)?.into())
+    }
+
+    fn part2(&self, lines: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(lines)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -82,7 +110,7 @@ mod test {
             treb7uchet
         "};
         let expected = 142;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(part1(&Day1.parse(input).unwrap()), Ok(expected));
     }
 
     #[test]
@@ -97,21 +125,6 @@ mod test {
             7pqrstsixteen
         "};
         let expected = 281;
-        assert_eq!(part2(&input), expected);
-    }
-
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+        assert_eq!(part2(&Day1.parse(input).unwrap()), Ok(expected));
     }
 }
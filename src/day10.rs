@@ -1,18 +1,17 @@
 use std::collections::HashSet;
 
-use itertools::iproduct;
+use itertools::Itertools;
 use parse_display::FromStr;
-use strum::{EnumIter, IntoEnumIterator};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Hash)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
 
-#[derive(Debug, FromStr, PartialEq, Eq)]
+use crate::{
+    render::{Picture, Rgb},
+    solution::{Answer, Day, SolveError},
+    utils::{self, geom::Direction, Grid, ParseError, Pos},
+};
+
+const DAY: u32 = 10;
+
+#[derive(Debug, Clone, Copy, FromStr, PartialEq, Eq)]
 enum Tile {
     #[display("S")]
     Start,
@@ -33,171 +32,374 @@ enum Tile {
 }
 
 impl Tile {
-    fn next_direction(&self, direction: Direction) -> Option<Direction> {
-        match (self, direction) {
-            (Self::Vertical, Direction::North) => Some(Direction::North),
-            (Self::Vertical, Direction::South) => Some(Direction::South),
-            (Self::Horizontal, Direction::East) => Some(Direction::East),
-            (Self::Horizontal, Direction::West) => Some(Direction::West),
+    const PIPES: [Self; 6] = [
+        Self::Vertical,
+        Self::Horizontal,
+        Self::NorthEast,
+        Self::NorthWest,
+        Self::SouthWest,
+        Self::SouthEast,
+    ];
 
-            (Self::NorthEast, Direction::South) => Some(Direction::East),
-            (Self::NorthEast, Direction::West) => Some(Direction::North),
+    /// Pipe connecting to both directions
+    fn from_connections(a: Direction, b: Direction) -> Option<Self> {
+        Self::PIPES.into_iter().find(|pipe| {
+            pipe.connections()
+                .is_some_and(|c| c.contains(&a) && c.contains(&b) && a != b)
+        })
+    }
 
-            (Self::NorthWest, Direction::South) => Some(Direction::West),
-            (Self::NorthWest, Direction::East) => Some(Direction::North),
+    /// Directions the pipe on this tile connects to
+    fn connections(&self) -> Option<[Direction; 2]> {
+        use Direction::*;
+        match self {
+            Self::Vertical => Some([North, South]),
+            Self::Horizontal => Some([East, West]),
+            Self::NorthEast => Some([North, East]),
+            Self::NorthWest => Some([North, West]),
+            Self::SouthWest => Some([South, West]),
+            Self::SouthEast => Some([South, East]),
+            Self::Start | Self::Ground => None,
+        }
+    }
 
-            (Self::SouthWest, Direction::North) => Some(Direction::West),
-            (Self::SouthWest, Direction::East) => Some(Direction::South),
+    /// Character drawing the tile, with box-drawing characters for pipes
+    fn box_char(&self) -> char {
+        match self {
+            Self::Start => 'S',
+            Self::Ground => '.',
+            Self::Vertical => '│',
+            Self::Horizontal => '─',
+            Self::NorthEast => '└',
+            Self::NorthWest => '┘',
+            Self::SouthWest => '┐',
+            Self::SouthEast => '┌',
+        }
+    }
 
-            (Self::SouthEast, Direction::North) => Some(Direction::East),
-            (Self::SouthEast, Direction::West) => Some(Direction::South),
+    /// Direction to leave the tile when entering it going in `direction`
+    fn next_direction(&self, direction: Direction) -> Option<Direction> {
+        let [a, b] = self.connections()?;
+        let from = direction.reverse();
+        match from {
+            _ if from == a => Some(b),
+            _ if from == b => Some(a),
             _ => None,
         }
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Tile>> {
-    let lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
-    lines
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect()
+#[derive(Debug)]
+pub struct Maze {
+    /// Tiles, with the start tile replaced by the pipe it hides
+    tiles: Grid<Tile>,
+    start: Pos,
+    pipe_loop: Loop,
+}
+
+/// Pipe hidden under the start tile, from the neighbours connecting to it
+fn resolve_start(tiles: &Grid<Tile>, start: Pos) -> Result<Tile, ParseError> {
+    let connections = Direction::ALL
+        .into_iter()
+        .filter(|dir| {
+            tiles
+                .step(start, *dir)
+                .and_then(|pos| tiles[pos].connections())
+                .is_some_and(|c| c.contains(&dir.reverse()))
         })
-        .collect()
+        .collect::<Vec<_>>();
+    match connections[..] {
+        [a, b] => Ok(Tile::from_connections(a, b).unwrap()),
+        _ => Err(ParseError {
+            day: DAY,
+            line: start.row + 1,
+            column: Some(start.col + 1),
+            expected: format!(
+                "a start tile `S` connected to 2 pipes, not {}",
+                connections.len()
+            ),
+        }),
+    }
 }
 
-fn get_next_pos_from_direction(
-    pos: (usize, usize),
-    direction: Direction,
-    grid_size: (usize, usize),
-) -> Option<(usize, usize)> {
-    match direction {
-        Direction::North => {
-            if pos.0 == 0 {
-                None
-            } else {
-                Some((pos.0 - 1, pos.1))
-            }
-        }
-        Direction::East => {
-            if pos.1 == grid_size.1 - 1 {
-                None
-            } else {
-                Some((pos.0, pos.1 + 1))
+fn parse_input(input: &str) -> Result<Maze, ParseError> {
+    let mut tiles = Grid::parse_cells(DAY, input, "a tile among `|-LJ7F.S`")?;
+    let start = tiles
+        .find(|tile| *tile == Tile::Start)
+        .ok_or_else(|| utils::unexpected_end(DAY, input, "a start tile `S`"))?;
+    tiles[start] = resolve_start(&tiles, start)?;
+    let pipe_loop = Loop::walk(&tiles, start).ok_or_else(|| ParseError {
+        day: DAY,
+        line: start.row + 1,
+        column: Some(start.col + 1),
+        expected: "a closed loop through `S`".into(),
+    })?;
+    Ok(Maze {
+        tiles,
+        start,
+        pipe_loop,
+    })
+}
+
+/// Closed path of pipes going through the start tile
+#[derive(Debug)]
+struct Loop {
+    /// Tiles of the loop in walking order, from the start tile
+    path: Vec<Pos>,
+}
+
+impl Loop {
+    /// Follows the pipes from the start tile, if they lead back to it
+    fn walk(tiles: &Grid<Tile>, start: Pos) -> Option<Self> {
+        let [mut dir, _] = tiles[start].connections()?;
+        let mut pos = start;
+        let mut path = Vec::new();
+        loop {
+            path.push(pos);
+            pos = tiles.step(pos, dir)?;
+            if pos == start {
+                return Some(Self { path });
             }
+            dir = tiles[pos].next_direction(dir)?;
         }
-        Direction::South => {
-            if pos.0 == grid_size.0 - 1 {
-                None
-            } else {
-                Some((pos.0 + 1, pos.1))
+    }
+
+    /// Tiles enclosed by the loop, whatever they contain, found by scanning
+    /// each row and crossing the loop on tiles connected to the north
+    fn enclosed_tiles(&self, maze: &Maze) -> Vec<Pos> {
+        let on_loop = self.path.iter().copied().collect::<HashSet<_>>();
+        let mut inside = false;
+        let mut enclosed = Vec::new();
+        for pos in maze.tiles.positions() {
+            if pos.col == 0 {
+                inside = false;
             }
-        }
-        Direction::West => {
-            if pos.1 == 0 {
-                None
-            } else {
-                Some((pos.0, pos.1 - 1))
+            if on_loop.contains(&pos) {
+                // Going along `L--7` or `F--J` crosses the loop once, while
+                // `L--J` and `F--7` touch it without crossing
+                let connections = maze.tiles[pos].connections();
+                if connections.is_some_and(|c| c.contains(&Direction::North)) {
+                    inside = !inside;
+                }
+            } else if inside {
+                enclosed.push(pos);
             }
         }
+        enclosed
+    }
+
+    fn enclosed_by_crossings(&self, maze: &Maze) -> u64 {
+        self.enclosed_tiles(maze).len() as u64
+    }
+
+    /// Tile of the loop farthest from the start, with its distance
+    fn farthest(&self) -> (Pos, usize) {
+        let steps = self.path.len() / 2;
+        (self.path[steps], steps)
+    }
+
+    /// Tiles enclosed by the loop from its area with the shoelace formula and
+    /// Pick's theorem, `A = I + B / 2 - 1`
+    fn enclosed_by_area(&self) -> u64 {
+        let corners = self.path.iter().chain(self.path.first());
+        let double_area = corners
+            .tuple_windows()
+            .map(|(a, b)| a.col as i64 * b.row as i64 - b.col as i64 * a.row as i64)
+            .sum::<i64>()
+            .unsigned_abs();
+        (double_area + 2 - self.path.len() as u64) / 2
     }
 }
 
-fn get_loop_path(
-    map: &[Vec<Tile>],
-    start: (usize, usize),
-    grid_size: (usize, usize),
-) -> Vec<(usize, usize)> {
-    let mut dir = Direction::iter()
-        .find(|dir| {
-            let next_pos = get_next_pos_from_direction(start, *dir, grid_size);
-            if let Some(pos) = next_pos {
-                map[pos.0][pos.1].next_direction(*dir).is_some()
-            } else {
-                false
-            }
-        })
-        .unwrap();
-    let mut pos = start;
-    let mut path = Vec::new();
-    loop {
-        path.push(pos);
-        let next_pos = get_next_pos_from_direction(pos, dir, grid_size).unwrap();
-        let next_dir = map[next_pos.0][next_pos.1].next_direction(dir).unwrap();
-        pos = next_pos;
-        dir = next_dir;
-        if pos == start {
-            break;
+fn part1(maze: &Maze) -> u64 {
+    let loop_size = maze.pipe_loop.path.len() as u64;
+    loop_size.div_ceil(2)
+}
+
+/// Tiles enclosed by the loop, if scanning the rows and Pick's theorem agree
+fn part2(maze: &Maze) -> Option<u64> {
+    let pipe_loop = &maze.pipe_loop;
+    let enclosed = pipe_loop.enclosed_by_crossings(maze);
+    (enclosed == pipe_loop.enclosed_by_area()).then_some(enclosed)
+}
+
+/// How a tile is drawn by `render`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Loop,
+    Start,
+    Farthest,
+    Enclosed,
+    Outside,
+}
+
+impl Style {
+    /// ANSI escape code selecting the style
+    fn ansi(self) -> &'static str {
+        match self {
+            Self::Loop => "\x1b[1;36m",
+            Self::Start => "\x1b[1;35m",
+            Self::Farthest => "\x1b[1;31m",
+            Self::Enclosed => "\x1b[1;33m",
+            Self::Outside => "\x1b[2m",
         }
     }
-    path
 }
 
-pub fn part1(input: &str) -> u64 {
-    let map = parse_input(input);
-    let grid_size = (map.len(), map[0].len());
-    let start = map
-        .iter()
-        .enumerate()
-        .find_map(|(i, row)| {
-            row.iter()
-                .enumerate()
-                .find(|(_, tile)| **tile == Tile::Start)
-                .map(|(j, _)| (i, j))
-        })
-        .unwrap();
-    let loop_path = get_loop_path(&map, start, grid_size);
-    let loop_size = loop_path.len() as u64;
-    loop_size.div_ceil(2)
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Style of every tile, given the loop of the maze
+fn tile_styles(maze: &Maze, pipe_loop: &Loop) -> Grid<Style> {
+    let mut styles = maze.tiles.map(|_| Style::Outside);
+    for pos in &pipe_loop.path {
+        styles[*pos] = Style::Loop;
+    }
+    for pos in pipe_loop.enclosed_tiles(maze) {
+        styles[pos] = Style::Enclosed;
+    }
+    styles[maze.start] = Style::Start;
+    styles[pipe_loop.farthest().0] = Style::Farthest;
+    styles
 }
 
-pub fn part2(input: &str) -> u64 {
-    let map = parse_input(input);
-    let grid_size = (map.len(), map[0].len());
-    let start = map
+/// Terminal drawing of the maze, with box-drawing characters for the pipes
+///
+/// The loop is highlighted, enclosed tiles are drawn as `I` and the loop tile
+/// farthest from the start as `*`, followed by a summary. Without `color`,
+/// the drawing is plain text where the tiles outside the loop are drawn as
+/// `·`, so that stray pipes can't be mistaken for the loop.
+pub fn render(maze: &Maze, color: bool) -> String {
+    let pipe_loop = &maze.pipe_loop;
+    let (farthest, steps) = pipe_loop.farthest();
+    let styles = tile_styles(maze, pipe_loop);
+    let enclosed = styles
         .iter()
-        .enumerate()
-        .find_map(|(i, row)| {
-            row.iter()
-                .enumerate()
-                .find(|(_, tile)| **tile == Tile::Start)
-                .map(|(j, _)| (i, j))
-        })
-        .unwrap();
-    let loop_path = get_loop_path(&map, start, grid_size);
-    let loop_group: HashSet<(usize, usize)> = HashSet::from_iter(loop_path.into_iter());
-    let mut is_capturing = false;
-    let mut is_first_line_item_capturing = is_capturing;
-    let mut captured = HashSet::new();
-    for (i, j) in iproduct!((0..grid_size.0), (0..grid_size.1)) {
-        if i == 0 {
-            is_capturing = is_first_line_item_capturing;
+        .filter(|(_, s)| **s == Style::Enclosed)
+        .count();
+
+    let mut output = String::new();
+    for (pos, tile) in maze.tiles.iter() {
+        let style = styles[pos];
+        let c = match style {
+            Style::Start => 'S',
+            Style::Farthest => '*',
+            Style::Enclosed => 'I',
+            Style::Loop => tile.box_char(),
+            Style::Outside if color => tile.box_char(),
+            Style::Outside => '·',
+        };
+        if color {
+            // Only switch styles when they change along the row
+            let previous = (pos.col > 0).then(|| styles[Pos::new(pos.row, pos.col - 1)]);
+            if previous != Some(style) {
+                if previous.is_some() {
+                    output.push_str(ANSI_RESET);
+                }
+                output.push_str(style.ansi());
+            }
         }
-        if loop_group.contains(&(i, j)) {
-            is_capturing = !is_capturing;
-            if i == 0 {
-                is_first_line_item_capturing = is_capturing;
+        output.push(c);
+        if pos.col + 1 == maze.tiles.width() {
+            if color {
+                output.push_str(ANSI_RESET);
             }
-            continue;
+            output.push('\n');
         }
-        if map[i][j] == Tile::Ground && is_capturing {
-            captured.insert((i, j));
+    }
+    output.push_str(&format!(
+        "Loop of {} tiles, farthest tile {} steps away at line {}, column {}\n",
+        pipe_loop.path.len(),
+        steps,
+        farthest.row + 1,
+        farthest.col + 1
+    ));
+    output.push_str(&format!("Enclosed tiles: {}\n", enclosed));
+    output
+}
+
+/// Picture of the maze, with the loop drawn over the other pipes and the
+/// enclosed tiles filled
+pub fn picture(maze: &Maze) -> Picture {
+    let styles = tile_styles(maze, &maze.pipe_loop);
+    let mut picture = Picture::new(maze.tiles.width(), maze.tiles.height());
+    for (pos, tile) in maze.tiles.iter() {
+        let cell = &mut picture[pos];
+        cell.lines = tile.connections().map_or(vec![], Vec::from);
+        cell.line_color = Rgb(190, 190, 190);
+        match styles[pos] {
+            Style::Loop => cell.line_color = Rgb(30, 70, 170),
+            Style::Start => {
+                cell.line_color = Rgb(30, 70, 170);
+                cell.background = Some(Rgb(215, 160, 255));
+            }
+            Style::Farthest => {
+                cell.line_color = Rgb(30, 70, 170);
+                cell.background = Some(Rgb(250, 130, 120));
+            }
+            Style::Enclosed => cell.background = Some(Rgb(255, 225, 110)),
+            Style::Outside => {}
         }
     }
-    captured.len() as u64
+    picture
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day10;
+
+impl Day for Day10 {
+    type Parsed<'a> = Maze;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Pipe Maze"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, maze: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(maze).into())
+    }
+
+    fn part2(&self, maze: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let enclosed = part2(maze).ok_or_else(|| SolveError::NoAnswer {
+            day: DAY,
+            reason: "the row scan and Pick's theorem disagree on the enclosed tiles".into(),
+        })?;
+        Ok(enclosed.into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::utils::test::assert_golden;
+
+    /// Loop touching the bounds of the maze
+    const BOUNDED_LOOP: &str = crate::utils::sample_input! {"
+    7-F7-
+    .FJ|7
+    SJLL7
+    |F--J
+    LJ.LJ
+    "};
+
+    /// Loop surrounded by pipes that aren't part of it
+    const JUNK_PIPES: &str = crate::utils::sample_input! {"
+    FF7FSF7F7F7F7F7F---7
+    L|LJ||||||||||||F--J
+    FL-7LJLJ||||||LJL-77
+    F--JF--7||LJLJ7F7FJ-
+    L---JF-JLJ.||-FJLJJ7
+    |F|F-JF---7F7-L7L|7|
+    |FFJF7L7F-JF7|JL---7
+    7-L-JL7||F7|L7F-7F7|
+    L.L7LFJ|||||FJL7||LJ
+    L7JLJL-JLJLJL--JLJ.L
+    "};
 
     #[test]
     fn test_part1() {
@@ -209,20 +411,16 @@ mod test {
         L|-JF
         "};
         let expected = 4;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(parse_input(input).map(|maze| part1(&maze)), Ok(expected));
     }
 
     #[test]
     fn test_part1_with_bounds() {
-        let input = crate::utils::sample_input! {"
-        7-F7-
-        .FJ|7
-        SJLL7
-        |F--J
-        LJ.LJ
-        "};
         let expected = 8;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(
+            parse_input(BOUNDED_LOOP).map(|maze| part1(&maze)),
+            Ok(expected)
+        );
     }
 
     #[test]
@@ -238,22 +436,73 @@ mod test {
         .L--J.L--J.
         ...........
         "};
-        let expected = 4;
-        assert_eq!(part2(&input), expected);
+        let expected = Some(4);
+        assert_eq!(parse_input(input).map(|maze| part2(&maze)), Ok(expected));
     }
 
-    extern crate test;
-    use test::test::Bencher;
+    #[test]
+    fn test_part2_squeezing_between_pipes() {
+        let input = crate::utils::sample_input! {"
+        .F----7F7F7F7F-7....
+        .|F--7||||||||FJ....
+        .||.FJ||||||||L7....
+        FJL7L7LJLJ||LJ.L-7..
+        L--J.L7...LJS7F-7L7.
+        ....F-J..F7FJ|L7L7L7
+        ....L7.F7||L7|.L7L7|
+        .....|FJLJ|FJ|F7|.LJ
+        ....FJL-7.||.||||...
+        ....L---J.LJ.LJLJ...
+        "};
+        let expected = Some(8);
+        assert_eq!(parse_input(input).map(|maze| part2(&maze)), Ok(expected));
+    }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
+    #[test]
+    fn test_part2_junk_pipes() {
+        let maze = parse_input(JUNK_PIPES).unwrap();
+        let pipe_loop = &maze.pipe_loop;
+        assert_eq!(pipe_loop.enclosed_by_crossings(&maze), 10);
+        assert_eq!(pipe_loop.enclosed_by_area(), 10);
+    }
+
+    #[test]
+    fn test_resolve_start() {
+        let maze = parse_input(BOUNDED_LOOP).unwrap();
+        assert_eq!(maze.tiles[maze.start], Tile::SouthEast);
+
+        let input = crate::utils::sample_input! {"
+        .|.
+        -S-
+        .|.
+        "};
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day10 line 2, column 2: expected a start tile `S` connected to 2 pipes, not 4"
+        );
+        let input = crate::utils::sample_input! {"
+        ...
+        .S-
+        ...
+        "};
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day10 line 2, column 2: expected a start tile `S` connected to 2 pipes, not 1"
+        );
+        let error = parse_input("S-\n|.\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day10 line 1, column 1: expected a closed loop through `S`"
+        );
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+    #[test]
+    fn test_render() {
+        let maze = parse_input(JUNK_PIPES).unwrap();
+        assert_golden("day10-plain.txt", &render(&maze, false));
+        assert_golden("day10-color.txt", &render(&maze, true));
+        assert_golden("day10.svg", &picture(&maze).to_svg());
     }
 }
@@ -1,13 +1,21 @@
-use itertools::{max, Itertools};
+use itertools::max;
 use parse_display::{Display, FromStr};
 
-struct Game {
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, ParseError},
+};
+
+const DAY: u32 = 2;
+
+#[derive(Debug)]
+pub struct Game {
     id: u64,
     sets: Vec<GameCubeSet>,
 }
 
 #[derive(Debug)]
-struct GameCubeSet {
+pub struct GameCubeSet {
     blue: u64,
     red: u64,
     green: u64,
@@ -39,30 +47,33 @@ enum GameCubes {
     Green(u64),
 }
 
-fn parse_games(lines: Vec<&str>) -> impl IntoIterator<Item = Game> + '_ {
-    lines
-        .iter()
+fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    utils::lines(DAY, input)
         .map(|line| {
-            let (game_str, sets_str) = line.split_once(": ").unwrap();
-            let id: u64 = game_str.strip_prefix("Game ").unwrap().parse().unwrap();
+            let (game_str, sets_str) = line
+                .text
+                .split_once(": ")
+                .ok_or_else(|| line.error("`Game <id>: <sets>`"))?;
+            let id_str = game_str
+                .strip_prefix("Game ")
+                .ok_or_else(|| line.error_at(game_str, "`Game <id>`"))?;
+            let id: u64 = line.parse(id_str, "a game id")?;
             let sets = sets_str
                 .split("; ")
                 .map(|set| {
-                    let cubes: Vec<GameCubes> =
-                        set.split(", ").map(|cube| cube.parse().unwrap()).collect();
-                    cubes.into_iter().collect::<GameCubeSet>()
+                    set.split(", ")
+                        .map(|cube| line.parse(cube, "`<count> <blue|red|green>`"))
+                        .collect::<Result<GameCubeSet, _>>()
                 })
-                .collect_vec();
-            Game { id, sets }
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Game { id, sets })
         })
-        .collect_vec()
+        .collect()
 }
 
-pub fn part1(input: &str) -> u64 {
-    let lines: Vec<&str> = input.lines().collect();
-    let games = parse_games(lines);
+fn part1(games: &[Game]) -> u64 {
     games
-        .into_iter()
+        .iter()
         .filter(|game| {
             game.sets.iter().all(|set| {
                 const MAX_RED: u64 = 12;
@@ -74,10 +85,8 @@ pub fn part1(input: &str) -> u64 {
         .fold(0, |acc, game| acc + game.id)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let lines: Vec<&str> = input.lines().collect();
-    let games = parse_games(lines);
-    let larger_cubesets = games.into_iter().map(|game| GameCubeSet {
+fn part2(games: &[Game]) -> u64 {
+    let larger_cubesets = games.iter().map(|game| GameCubeSet {
         blue: max(game.sets.iter().map(|set| set.blue)).unwrap_or(0),
         red: max(game.sets.iter().map(|set| set.red)).unwrap_or(0),
         green: max(game.sets.iter().map(|set| set.green)).unwrap_or(0),
@@ -86,10 +95,30 @@ pub fn part2(input: &str) -> u64 {
     powers.sum()
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day2;
+
+impl Day for Day2 {
+    type Parsed<'a> = Vec<Game>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Cube Conundrum"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_games(input)
+    }
+
+    fn part1(&self, games: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(games).into())
+    }
+
+    fn part2(&self, games: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(games).into())
+    }
 }
 
 #[cfg(test)]
@@ -106,7 +135,7 @@ mod test {
         Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         "};
         let expected = 8;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(parse_games(input).map(|games| part1(&games)), Ok(expected));
     }
 
     #[test]
@@ -119,21 +148,19 @@ mod test {
         Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         "};
         let expected = 2286;
-        assert_eq!(part2(&input), expected);
+        assert_eq!(parse_games(input).map(|games| part2(&games)), Ok(expected));
     }
 
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+    #[test]
+    fn test_invalid_cube_color() {
+        let input = crate::utils::sample_input! {"
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 purple; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        "};
+        let error = parse_games(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day2 line 2, column 17: expected `<count> <blue|red|green>`"
+        );
     }
 }
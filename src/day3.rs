@@ -1,160 +1,308 @@
-use std::{collections::HashSet, iter::once};
-
 use itertools::Itertools;
 
+use crate::{
+    render::{Picture, Rgb},
+    solution::{Answer, Day, SolveError},
+    utils::{Grid, ParseError, Pos},
+};
+
+const DAY: u32 = 3;
+
 #[derive(Debug)]
-struct Symbol {
+pub struct Symbol {
     value: char,
-    line: usize,
-    col: usize,
+    pos: Pos,
 }
 
 #[derive(Debug)]
-struct Number {
+pub struct Number {
     value: u64,
-    line: usize,
-    col_start: usize,
+    /// Position of the first digit
+    start: Pos,
+    /// Column of the last digit
     col_end: usize,
 }
 
-fn parse_input(input: &str) -> (Vec<Symbol>, Vec<Number>) {
-    let lines = input.lines().collect_vec();
-    // Ordered by (line, col)
+impl Symbol {
+    pub fn value(&self) -> char {
+        self.value
+    }
+
+    pub fn pos(&self) -> Pos {
+        self.pos
+    }
+}
+
+impl Number {
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+#[derive(Debug)]
+pub struct Schematic {
+    symbols: Vec<Symbol>,
+    /// Ordered by (row, col)
+    numbers: Vec<Number>,
+    /// Index in `numbers` of the number covering each cell
+    number_ids: Grid<Option<usize>>,
+}
+
+impl Schematic {
+    /// Indices of the numbers adjacent to a symbol, each once and in order
+    fn adjacent_ids(&self, symbol: &Symbol) -> Vec<usize> {
+        let mut ids = self
+            .number_ids
+            .neighbours8(symbol.pos)
+            .filter_map(|pos| self.number_ids[pos])
+            .collect_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Numbers adjacent to a symbol, in reading order
+    pub fn adjacent_numbers(&self, symbol: &Symbol) -> Vec<&Number> {
+        let ids = self.adjacent_ids(symbol);
+        ids.into_iter().map(|id| &self.numbers[id]).collect()
+    }
+
+    /// Every symbol with the numbers adjacent to it
+    pub fn symbols_with_numbers(&self) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols
+            .iter()
+            .map(|symbol| (symbol, self.adjacent_numbers(symbol)))
+    }
+
+    /// Symbols adjacent to exactly `count` numbers
+    pub fn symbols_with_neighbours(&self, count: usize) -> impl Iterator<Item = &Symbol> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| self.adjacent_ids(symbol).len() == count)
+    }
+
+    /// Whether each number is adjacent to a symbol, by index in `numbers`
+    fn is_part(&self) -> Vec<bool> {
+        let mut is_part = vec![false; self.numbers.len()];
+        for symbol in &self.symbols {
+            for id in self.adjacent_ids(symbol) {
+                is_part[id] = true;
+            }
+        }
+        is_part
+    }
+
+    /// Numbers adjacent to at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        let is_part = self.is_part();
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(move |(id, _)| is_part[*id])
+            .map(|(_, number)| number)
+    }
+}
+
+fn parse_input(input: &str) -> Result<Schematic, ParseError> {
+    let grid = Grid::<char>::parse_cells(DAY, input, "a character")?;
     let mut numbers = vec![];
     let mut symbols = vec![];
-    lines.iter().enumerate().for_each(|(i, l)| {
-        let mut chars = l.chars().enumerate().peekable();
-        while let Some((col, c)) = chars.next() {
-            if c.is_ascii_digit() {
-                let col_start = col;
-                let other_digits = chars
-                    .peeking_take_while(|(_, c)| c.is_ascii_digit())
-                    .map(|(_, c)| c);
-                let digits = once(c).chain(other_digits).collect::<String>();
-                let value = digits.parse::<u64>().unwrap();
-                let col_end = col_start + digits.len() - 1;
+    for (row, cells) in grid.rows().enumerate() {
+        let mut cells = cells.iter().enumerate().peekable();
+        while let Some((col, c)) = cells.next() {
+            if let Some(digit) = c.to_digit(10) {
+                let start = Pos::new(row, col);
+                let mut value = Some(u64::from(digit));
+                let mut col_end = col;
+                for (col, c) in cells.peeking_take_while(|(_, c)| c.is_ascii_digit()) {
+                    let digit = u64::from(c.to_digit(10).unwrap());
+                    value = value.and_then(|v| v.checked_mul(10)?.checked_add(digit));
+                    col_end = col;
+                }
+                let value = value.ok_or_else(|| ParseError {
+                    day: DAY,
+                    line: row + 1,
+                    column: Some(col + 1),
+                    expected: "a part number".to_string(),
+                })?;
                 numbers.push(Number {
                     value,
-                    line: i,
-                    col_start,
+                    start,
                     col_end,
                 });
-            } else if c != '.' {
+            } else if *c != '.' {
                 symbols.push(Symbol {
-                    value: c,
-                    line: i,
-                    col,
+                    value: *c,
+                    pos: Pos::new(row, col),
                 });
             }
         }
-    });
-    (symbols, numbers)
+    }
+    let mut number_ids = Grid::new(grid.width(), grid.height(), None);
+    for (id, number) in numbers.iter().enumerate() {
+        for col in number.start.col..=number.col_end {
+            number_ids[Pos::new(number.start.row, col)] = Some(id);
+        }
+    }
+    Ok(Schematic {
+        symbols,
+        numbers,
+        number_ids,
+    })
 }
 
-fn is_symbol_close_to_number(symbol: &Symbol, number: &Number) -> bool {
-    let are_lines_close = (symbol.line as i64 - number.line as i64).abs() <= 1;
-    let col_dist = if symbol.col < number.col_start {
-        number.col_start - symbol.col
-    } else if symbol.col > number.col_end {
-        symbol.col - number.col_end
-    } else {
-        0
-    };
-    are_lines_close && col_dist <= 1
+fn part1(schematic: &Schematic) -> u64 {
+    schematic.part_numbers().map(|number| number.value).sum()
 }
 
-// Complexity: O(n^2)
-pub fn part1(input: &str) -> u64 {
-    let (symbols, numbers) = parse_input(input);
-    let mut part_number_idxs = HashSet::new();
-    for symbol in symbols.iter() {
-        let new_parts_idx = numbers
-            .iter()
-            .enumerate()
-            .filter(|(_, n)| is_symbol_close_to_number(symbol, n))
-            .map(|(i, _)| i);
-        part_number_idxs.extend(new_parts_idx);
-    }
-    part_number_idxs.iter().map(|i| numbers[*i].value).sum()
+fn part2(schematic: &Schematic) -> u64 {
+    schematic
+        .symbols_with_numbers()
+        .filter(|(symbol, _)| symbol.value == '*')
+        .filter_map(|(_, numbers)| match numbers[..] {
+            [a, b] => Some(a.value * b.value),
+            _ => None,
+        })
+        .sum()
 }
 
-// Complexity: O(n^2)
-pub fn part2(input: &str) -> u64 {
-    let (symbols, numbers) = parse_input(input);
-    let gears = symbols.iter().filter(|s| s.value == '*');
-    let mut gear_ratio_sum = 0;
-    for gear in gears {
-        let numbers_close_to_symbol = numbers
-            .iter()
-            .enumerate()
-            .filter(|(_, n)| is_symbol_close_to_number(gear, n))
-            .map(|(_, n)| n.value)
-            .collect_vec();
-        if numbers_close_to_symbol.len() == 2 {
-            gear_ratio_sum += numbers_close_to_symbol[0] * numbers_close_to_symbol[1];
+/// Picture of the schematic, filling part numbers, numbers adjacent to no
+/// symbol, gears and other symbols with different colours
+pub fn picture(schematic: &Schematic) -> Picture {
+    let grid = &schematic.number_ids;
+    let mut picture = Picture::new(grid.width(), grid.height());
+    let is_part = schematic.is_part();
+    for (id, number) in schematic.numbers.iter().enumerate() {
+        let background = if is_part[id] {
+            Rgb(170, 230, 160)
+        } else {
+            Rgb(250, 170, 160)
+        };
+        let len = number.col_end - number.start.col + 1;
+        let digits = format!("{:0len$}", number.value);
+        for (i, digit) in digits.chars().enumerate() {
+            let cell = &mut picture[Pos::new(number.start.row, number.start.col + i)];
+            cell.background = Some(background);
+            cell.label = Some(digit);
         }
     }
-    gear_ratio_sum
+    for (symbol, numbers) in schematic.symbols_with_numbers() {
+        let cell = &mut picture[symbol.pos];
+        cell.background = Some(if symbol.value == '*' && numbers.len() == 2 {
+            Rgb(255, 200, 60)
+        } else {
+            Rgb(200, 200, 200)
+        });
+        cell.label = Some(symbol.value);
+    }
+    picture
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day3;
+
+impl Day for Day3 {
+    type Parsed<'a> = Schematic;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Gear Ratios"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, schematic: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(schematic).into())
+    }
+
+    fn part2(&self, schematic: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(schematic).into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::utils::test::assert_golden;
+
+    const SAMPLE: &str = crate::utils::sample_input! {"
+    467..114..
+    ...*......
+    ..35..633.
+    ......#...
+    617*......
+    .....+.58.
+    ..592.....
+    ......755.
+    ...$.*....
+    .664.598..
+    "};
 
     #[test]
     fn test_part1() {
-        let input = crate::utils::sample_input! {"
-        467..114..
-        ...*......
-        ..35..633.
-        ......#...
-        617*......
-        .....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..
-        "};
         let expected = 4361;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(
+            parse_input(SAMPLE).map(|schematic| part1(&schematic)),
+            Ok(expected)
+        );
     }
 
     #[test]
     fn test_part2() {
-        let input = crate::utils::sample_input! {"
-        467..114..
-        ...*......
-        ..35..633.
-        ......#...
-        617*......
-        .....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..
-        "};
         let expected = 467835;
-        assert_eq!(part2(&input), expected);
+        assert_eq!(
+            parse_input(SAMPLE).map(|schematic| part2(&schematic)),
+            Ok(expected)
+        );
     }
 
-    extern crate test;
-    use test::test::Bencher;
+    #[test]
+    fn test_part_number_overflow() {
+        let input = "1.......................\n.*9999999999999999999999\n";
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day3 line 2, column 3: expected a part number"
+        );
+    }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
+    #[test]
+    fn test_picture() {
+        let schematic = parse_input(SAMPLE).unwrap();
+        assert_golden("day3.svg", &picture(&schematic).to_svg());
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+    #[test]
+    fn test_adjacency() {
+        let schematic = parse_input(SAMPLE).unwrap();
+        let values = |numbers: Vec<&Number>| numbers.iter().map(|n| n.value()).collect_vec();
+        let star = &schematic.symbols[0];
+        assert_eq!((star.value(), star.pos()), ('*', Pos::new(1, 3)));
+        assert_eq!(values(schematic.adjacent_numbers(star)), vec![467, 35]);
+        let single = schematic
+            .symbols_with_neighbours(1)
+            .map(|s| s.value())
+            .collect::<String>();
+        assert_eq!(single, "#*+$");
+        assert_eq!(schematic.symbols_with_neighbours(0).count(), 0);
+        assert_eq!(schematic.part_numbers().count(), 8);
+
+        // Every number adjacent to a symbol, found by comparing positions
+        for symbol in &schematic.symbols {
+            let expected = schematic
+                .numbers
+                .iter()
+                .filter(|n| {
+                    symbol.pos.row.abs_diff(n.start.row) <= 1
+                        && symbol.pos.col + 1 >= n.start.col
+                        && symbol.pos.col <= n.col_end + 1
+                })
+                .collect_vec();
+            assert_eq!(values(schematic.adjacent_numbers(symbol)), values(expected));
+        }
     }
 }
@@ -1,66 +1,57 @@
 use std::collections::HashSet;
 
-use itertools::Itertools;
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, Line, ParseError},
+};
 
-struct Card {
-    id: u64,
+const DAY: u32 = 4;
+
+pub struct Card {
     winning_numbers: HashSet<u64>,
     player_numbers: HashSet<u64>,
 }
 
-impl From<String> for Card {
-    fn from(s: String) -> Self {
-        let mut parts = s.split(": ");
-        let id = parts
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .nth(1)
-            .unwrap()
-            .parse()
-            .unwrap();
-        let mut numbers = parts.next().unwrap().split(" | ");
-        let winning_numbers = numbers
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .map(|n| n.trim().parse().unwrap())
-            .collect();
-        let player_numbers = numbers
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .map(|n| n.trim().parse().unwrap())
-            .collect();
-        Self {
-            id,
-            winning_numbers,
-            player_numbers,
-        }
+impl TryFrom<Line<'_>> for Card {
+    type Error = ParseError;
+
+    fn try_from(line: Line<'_>) -> Result<Self, Self::Error> {
+        let (_, numbers) = line
+            .text
+            .split_once(": ")
+            .ok_or_else(|| line.error("`Card <id>: <numbers>`"))?;
+        let (winning, player) = numbers
+            .split_once(" | ")
+            .ok_or_else(|| line.error_at(numbers, "`<winning numbers> | <numbers>`"))?;
+        let parse_numbers = |numbers: &str| {
+            numbers
+                .split_whitespace()
+                .map(|n| line.parse(n, "a number"))
+                .collect::<Result<HashSet<u64>, _>>()
+        };
+        Ok(Self {
+            winning_numbers: parse_numbers(winning)?,
+            player_numbers: parse_numbers(player)?,
+        })
     }
 }
 
-pub fn part1(input: &str) -> u64 {
-    let lines = input.lines().collect_vec();
-    lines
-        .iter()
-        .map(|l| Card::from(l.to_string()))
-        .map(|c| {
-            let matches = c.player_numbers.intersection(&c.winning_numbers).count();
-            if matches == 0 {
-                return 0;
-            }
-            2u64.pow((matches - 1) as u32)
-        })
-        .sum()
+fn parse_input(input: &str) -> Result<Vec<Card>, ParseError> {
+    utils::lines(DAY, input).map(Card::try_from).collect()
 }
 
-pub fn part2(input: &str) -> u64 {
-    let lines = input.lines().collect_vec();
-    let cards = lines
-        .iter()
-        .map(|l| Card::from(l.to_string()))
-        .collect_vec();
+fn part1(cards: &[Card]) -> u64 {
+    let points = cards.iter().map(|c| {
+        let matches = c.player_numbers.intersection(&c.winning_numbers).count();
+        if matches == 0 {
+            return 0;
+        }
+        2u64.pow((matches - 1) as u32)
+    });
+    points.sum()
+}
+
+fn part2(cards: &[Card]) -> u64 {
     let mut card_count = vec![1u64; cards.len()];
     for (i, c) in cards.iter().enumerate() {
         let matches = c.player_numbers.intersection(&c.winning_numbers).count();
@@ -71,10 +62,30 @@ pub fn part2(input: &str) -> u64 {
     card_count.iter().sum()
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day4;
+
+impl Day for Day4 {
+    type Parsed<'a> = Vec<Card>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Scratchcards"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, cards: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(cards).into())
+    }
+
+    fn part2(&self, cards: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(cards).into())
+    }
 }
 
 #[cfg(test)]
@@ -92,7 +103,7 @@ mod test {
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
         let expected = 13;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(parse_input(input).map(|cards| part1(&cards)), Ok(expected));
     }
 
     #[test]
@@ -106,21 +117,6 @@ mod test {
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
         let expected = 30;
-        assert_eq!(part2(&input), expected);
-    }
-
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+        assert_eq!(parse_input(input).map(|cards| part2(&cards)), Ok(expected));
     }
 }
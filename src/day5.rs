@@ -1,186 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
 use itertools::Itertools;
 
-#[derive(Debug)]
-struct Map {
-    sources: Vec<u64>,
-    destinations: Vec<u64>,
-    lengths: Vec<usize>,
-}
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{
+        self,
+        intervals::{Interval, IntervalSet},
+        piecewise::{Piece, PiecewiseMap},
+        ParseError,
+    },
+};
+
+const DAY: u32 = 5;
 
-#[derive(Debug, Clone)]
-struct Range {
-    start: u64,
-    length: u64,
+#[derive(Debug)]
+pub struct Almanac<'a> {
+    seeds: Vec<u64>,
+    /// Maps between categories, by source and then destination category
+    maps: BTreeMap<&'a str, BTreeMap<&'a str, PiecewiseMap<u64>>>,
+    seed_to_location: PiecewiseMap<u64>,
 }
 
-impl Range {
-    /// Returns the last index of the range (inclusive)
-    fn last(&self) -> u64 {
-        self.start + self.length
+impl<'a> Almanac<'a> {
+    /// Seeds read as pairs of a range start and length
+    pub fn seed_ranges(&self) -> IntervalSet<u64> {
+        self.seeds
+            .chunks(2)
+            .map(|chunk| Interval::with_len(chunk[0], chunk[1]).unwrap())
+            .collect()
     }
 
-    fn intersection(&self, other: &Range) -> Option<Range> {
-        let start = self.start.max(other.start);
-        let end = self.last().min(other.last());
-        if start > end {
-            return None;
-        }
-        Some(Range {
-            start,
-            length: end - start,
-        })
+    /// Categories with a map from or to them
+    pub fn categories(&self) -> BTreeSet<&'a str> {
+        let destinations = self.maps.values().flat_map(|maps| maps.keys());
+        self.maps.keys().chain(destinations).copied().collect()
     }
-}
 
-impl Map {
-    fn get_destination(&self, input: u64) -> u64 {
-        for i in 0..self.sources.len() {
-            let source = self.sources[i];
-            let length = self.lengths[i];
-            if input >= source && input < source + length as u64 {
-                return self.destinations[i] + input - source;
+    /// Map converting values from one category to another, composing the
+    /// maps along a shortest chain between them
+    pub fn conversion(&self, from: &str, to: &str) -> Option<PiecewiseMap<u64>> {
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = vec![to];
+                while let Some(category) = previous.get(path[path.len() - 1]) {
+                    path.push(*category);
+                }
+                let map = path
+                    .windows(2)
+                    .rev()
+                    .fold(PiecewiseMap::identity(), |acc, w| {
+                        acc.then(&self.maps[w[1]][w[0]])
+                    });
+                return Some(map);
             }
-        }
-        input
-    }
-
-    fn get_destination_ranges(&self, range: &Range) -> Vec<Range> {
-        let mut input_ranges = Vec::new();
-        let mut ranges = Vec::new();
-
-        // Compute possible intersections
-        for i in 0..self.sources.len() {
-            if let Some(intersection) = range.intersection(&Range {
-                start: self.sources[i],
-                length: self.lengths[i] as u64,
-            }) {
-                let length = intersection.last() - intersection.start;
-                ranges.push(Range {
-                    start: self.destinations[i] + intersection.start - self.sources[i],
-                    length,
-                });
-                input_ranges.push(intersection);
+            for next in self
+                .maps
+                .get(category)
+                .into_iter()
+                .flat_map(|maps| maps.keys())
+            {
+                if *next != from && !previous.contains_key(*next) {
+                    previous.insert(*next, category);
+                    queue.push_back(*next);
+                }
             }
         }
+        None
+    }
 
-        // If there are no intersections, return the unchanged range
-        if input_ranges.is_empty() {
-            return vec![range.clone()];
-        }
+    /// Converts a value from one category to another
+    pub fn convert(&self, value: u64, from: &str, to: &str) -> Option<u64> {
+        Some(self.conversion(from, to)?.eval(value))
+    }
 
-        input_ranges.sort_by_key(|r| r.start);
-
-        // Add the ranges before the first intersection
-        if input_ranges[0].start > range.start {
-            ranges.insert(
-                0,
-                Range {
-                    start: range.start,
-                    length: input_ranges[0].start - range.start,
-                },
-            );
-        }
-        // Fill the gaps between intersections
-        for i in 0..input_ranges.len() - 1 {
-            if input_ranges[i].last() < input_ranges[i + 1].start {
-                ranges.push(Range {
-                    start: input_ranges[i].last(),
-                    length: input_ranges[i + 1].start - input_ranges[i].last(),
-                });
-            }
-        }
-        // Add the ranges after the last intersection
-        if input_ranges[input_ranges.len() - 1].last() < range.last() {
-            ranges.push(Range {
-                start: input_ranges[input_ranges.len() - 1].last(),
-                length: range.last() - input_ranges[input_ranges.len() - 1].last(),
-            });
-        }
-        ranges
+    /// Seeds of the seed ranges ending up in `locations`
+    pub fn seeds_into(&self, locations: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.seed_to_location
+            .preimage(locations)
+            .intersection(&self.seed_ranges())
     }
 }
 
-fn parse_input(input: &str) -> (Vec<u64>, Vec<Map>) {
-    let lines = input.lines().collect_vec();
-    let mut lines_iter = lines.iter().peekable();
-    let seeds = lines_iter
+fn parse_input(input: &str) -> Result<Almanac<'_>, ParseError> {
+    let mut lines = utils::lines(DAY, input).peekable();
+    let seeds_line = lines
         .next()
-        .unwrap()
-        .split(": ")
-        .nth(1)
-        .unwrap()
+        .ok_or_else(|| utils::unexpected_end(DAY, input, "`seeds: <seeds>`"))?;
+    let tokens = seeds_line
+        .text
+        .strip_prefix("seeds: ")
+        .ok_or_else(|| seeds_line.error("`seeds: <seeds>`"))?
         .split_whitespace()
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
-    let mut maps = Vec::new();
-    loop {
-        lines_iter.next();
-        let map_lines = lines_iter
-            .take_while_ref(|l| !l.is_empty())
-            .skip(1)
-            .collect_vec();
-        if map_lines.is_empty() {
+        .collect_vec();
+    let seeds = tokens
+        .iter()
+        .map(|s| seeds_line.parse::<u64>(s, "a seed number"))
+        .collect::<Result<Vec<_>, _>>()?;
+    if seeds.is_empty() {
+        return Err(seeds_line.error("at least one seed"));
+    }
+    // Part 2 reads the seeds as ranges, which must all be non-empty
+    if seeds.len() % 2 != 0 {
+        return Err(seeds_line.error("pairs of seed range starts and lengths"));
+    }
+    for (chunk, tokens) in seeds.chunks(2).zip(tokens.chunks(2)) {
+        if chunk[1] == 0 || Interval::with_len(chunk[0], chunk[1]).is_none() {
+            return Err(seeds_line.error_at(tokens[1], "a non-empty seed range"));
+        }
+    }
+    let mut maps: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+    while let Some(separator) = lines.next() {
+        if !separator.text.is_empty() {
+            return Err(separator.error("an empty line"));
+        }
+        let Some(header) = lines.next() else {
             break;
+        };
+        let (source_category, destination_category) = header
+            .text
+            .strip_suffix(" map:")
+            .and_then(|categories| categories.split_once("-to-"))
+            .ok_or_else(|| header.error("a `<source>-to-<destination> map:` header"))?;
+        let mut pieces = Vec::new();
+        for line in lines.peeking_take_while(|l| !l.text.is_empty()) {
+            let (destination, source, length) = line
+                .text
+                .split_whitespace()
+                .collect_tuple()
+                .ok_or_else(|| line.error("3 integers in map entry"))?;
+            let destination = line.parse::<u64>(destination, "a destination start")?;
+            let source_start = line.parse::<u64>(source, "a source start")?;
+            let length = line.parse::<u64>(length, "a range length")?;
+            let source = Interval::with_len(source_start, length)
+                .filter(|_| destination.checked_add(length).is_some())
+                .ok_or_else(|| line.error("ranges fitting in 64 bits"))?;
+            pieces.push(Piece {
+                source,
+                destination,
+            });
         }
-        let mut sources = Vec::new();
-        let mut destinations = Vec::new();
-        let mut lengths = Vec::new();
-        for line in map_lines {
-            let (destination, source, length) = line.split_whitespace().collect_tuple().unwrap();
-            sources.push(source.parse::<u64>().unwrap());
-            destinations.push(destination.parse::<u64>().unwrap());
-            lengths.push(length.parse::<usize>().unwrap());
+        let map = PiecewiseMap::new(pieces)
+            .ok_or_else(|| header.error("non-overlapping source ranges"))?;
+        let duplicate = maps
+            .entry(source_category)
+            .or_default()
+            .insert(destination_category, map)
+            .is_some();
+        if duplicate {
+            return Err(header.error(format!(
+                "a single `{}-to-{}` map",
+                source_category, destination_category
+            )));
         }
-        maps.push(Map {
-            sources,
-            destinations,
-            lengths,
-        });
     }
-    (seeds, maps)
+    let mut almanac = Almanac {
+        seeds,
+        maps,
+        seed_to_location: PiecewiseMap::identity(),
+    };
+    almanac.seed_to_location = almanac.conversion("seed", "location").ok_or_else(|| {
+        utils::unexpected_end(DAY, input, "maps leading from `seed` to `location`")
+    })?;
+    Ok(almanac)
 }
 
-pub fn part1(input: &str) -> u64 {
-    let (seeds, maps) = parse_input(input);
-    let locations = seeds
+fn part1(almanac: &Almanac) -> u64 {
+    let locations = almanac
+        .seeds
         .iter()
-        .map(|seed| maps.iter().fold(*seed, |acc, map| map.get_destination(acc)));
+        .map(|seed| almanac.seed_to_location.eval(*seed));
     locations.min().unwrap()
 }
 
-pub fn part2(input: &str) -> u64 {
-    let (seeds, maps) = parse_input(input);
-    let mut ranges = seeds
-        .chunks(2)
-        .map(|chunk| Range {
-            start: chunk[0],
-            length: chunk[1],
-        })
-        .collect_vec();
+fn part2(almanac: &Almanac) -> u64 {
+    let locations = almanac.seed_to_location.eval_set(&almanac.seed_ranges());
+    locations.min().unwrap()
+}
 
-    for map in maps.iter() {
-        let mut new_ranges = Vec::new();
-        for range in ranges.iter() {
-            new_ranges.extend(map.get_destination_ranges(range));
-        }
-        ranges = new_ranges;
+pub struct Day5;
+
+impl Day for Day5 {
+    type Parsed<'a> = Almanac<'a>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
     }
-    ranges.iter().map(|r| r.start).min().unwrap()
-}
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, almanac: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(almanac).into())
+    }
+
+    fn part2(&self, almanac: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(almanac).into())
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::utils::piecewise::arb_map;
 
-    #[test]
-    fn test_part1() {
-        let input = crate::utils::sample_input! {"
+    const SAMPLE: &str = crate::utils::sample_input! {"
         seeds: 79 14 55 13
 
         seed-to-soil map:
@@ -214,64 +247,174 @@ mod test {
         humidity-to-location map:
         60 56 37
         56 93 4
-        "};
+    "};
+
+    /// Almanac chaining the maps from `seed` to `location`
+    fn chained_almanac(seeds: Vec<u64>, chain: Vec<PiecewiseMap<u64>>) -> Almanac<'static> {
+        const CATEGORIES: [&str; 4] = ["seed", "soil", "fertilizer", "water"];
+        let len = chain.len();
+        let mut maps: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+        for (i, map) in chain.into_iter().enumerate() {
+            let destination = if i + 1 == len {
+                "location"
+            } else {
+                CATEGORIES[i + 1]
+            };
+            maps.entry(CATEGORIES[i])
+                .or_default()
+                .insert(destination, map);
+        }
+        let mut almanac = Almanac {
+            seeds,
+            maps,
+            seed_to_location: PiecewiseMap::identity(),
+        };
+        almanac.seed_to_location = almanac.conversion("seed", "location").unwrap();
+        almanac
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part2_matches_brute_force(
+            seeds in prop::collection::vec((0u64..120, 1u64..20), 1..4),
+            maps in prop::collection::vec(arb_map::<u64>(), 1..5),
+        ) {
+            let brute_force = seeds
+                .iter()
+                .flat_map(|&(start, len)| start..start + len)
+                .map(|seed| maps.iter().fold(seed, |acc, map| map.eval(acc)))
+                .min();
+            let seeds = seeds.into_iter().flat_map(|(start, len)| [start, len]).collect();
+            prop_assert_eq!(Some(part2(&chained_almanac(seeds, maps))), brute_force);
+        }
+    }
+
+    #[test]
+    fn test_part1() {
         let expected = 35;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(
+            parse_input(SAMPLE).map(|almanac| part1(&almanac)),
+            Ok(expected)
+        );
     }
 
     #[test]
     fn test_part2() {
+        let expected = 46;
+        assert_eq!(
+            parse_input(SAMPLE).map(|almanac| part2(&almanac)),
+            Ok(expected)
+        );
+        let almanac = parse_input(SAMPLE).unwrap();
+        assert_eq!(
+            almanac.seeds_into(&Interval::new(46, 47).into()),
+            Interval::new(82, 83).into()
+        );
+    }
+
+    #[test]
+    fn test_truncated_map_entry() {
         let input = crate::utils::sample_input! {"
         seeds: 79 14 55 13
 
         seed-to-soil map:
         50 98 2
-        52 50 48
+        52 50
+        "};
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day5 line 5: expected 3 integers in map entry"
+        );
+    }
 
-        soil-to-fertilizer map:
-        0 15 37
-        37 52 2
-        39 0 15
+    #[test]
+    fn test_invalid_seed_ranges() {
+        let error = |seeds: &str| {
+            let input = format!("seeds: {}\n\nseed-to-location map:\n50 98 2\n", seeds);
+            parse_input(&input).unwrap_err().to_string()
+        };
+        assert_eq!(
+            error("5 0"),
+            "day5 line 1, column 10: expected a non-empty seed range"
+        );
+        assert_eq!(
+            error("79 14 55"),
+            "day5 line 1: expected pairs of seed range starts and lengths"
+        );
+    }
 
-        fertilizer-to-water map:
-        49 53 8
-        0 11 42
-        42 0 7
-        57 7 4
+    #[test]
+    fn test_missing_separator() {
+        let input = "seeds: 79 14\nseed-to-location map:\n50 98 2\n";
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(error.to_string(), "day5 line 2: expected an empty line");
+    }
 
-        water-to-light map:
-        88 18 7
-        18 25 70
+    #[test]
+    fn test_overlapping_map_entries() {
+        let input = crate::utils::sample_input! {"
+        seeds: 79 14 55 13
 
-        light-to-temperature map:
-        45 77 23
-        81 45 19
-        68 64 13
+        seed-to-soil map:
+        50 98 2
+        52 50 49
+        "};
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day5 line 3: expected non-overlapping source ranges"
+        );
+    }
 
-        temperature-to-humidity map:
-        0 69 1
-        1 0 69
+    #[test]
+    fn test_conversions_out_of_order() {
+        let mut sections = SAMPLE.trim_end().split("\n\n").collect_vec();
+        // List the maps from `humidity-to-location` back to `seed-to-soil`
+        sections[1..].reverse();
+        let input = sections.join("\n\n");
+        let almanac = parse_input(&input).unwrap();
+        assert_eq!((part1(&almanac), part2(&almanac)), (35, 46));
+        assert_eq!(almanac.categories().len(), 8);
+        assert_eq!(almanac.convert(79, "seed", "soil"), Some(81));
+        assert_eq!(almanac.convert(81, "soil", "water"), Some(81));
+        assert_eq!(almanac.convert(14, "seed", "location"), Some(43));
+        assert_eq!(almanac.convert(78, "light", "humidity"), Some(47));
+        assert_eq!(almanac.convert(7, "seed", "seed"), Some(7));
+        assert_eq!(almanac.convert(82, "location", "seed"), None);
+        assert_eq!(almanac.convert(82, "seed", "unknown"), None);
+    }
 
-        humidity-to-location map:
-        60 56 37
-        56 93 4
+    #[test]
+    fn test_broken_chain() {
+        let input = crate::utils::sample_input! {"
+        seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+
+        water-to-location map:
+        52 50 48
         "};
-        let expected = 46;
-        assert_eq!(part2(&input), expected);
-    }
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day5 line 8: expected maps leading from `seed` to `location`"
+        );
 
-    extern crate test;
-    use test::test::Bencher;
+        let input = crate::utils::sample_input! {"
+        seeds: 79 14 55 13
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
+        seed-to-location map:
+        50 98 2
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+        seed-to-location map:
+        52 50 48
+        "};
+        let error = parse_input(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day5 line 6: expected a single `seed-to-location` map"
+        );
     }
 }
@@ -1,21 +1,74 @@
 use itertools::Itertools;
 
-struct Race {
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, Line, ParseError},
+};
+
+const DAY: u32 = 6;
+
+#[derive(Debug)]
+pub struct Race {
     duration: u64,
     record_distance: u64,
 }
 
-fn parse_input(input: &str) -> Vec<Race> {
-    let lines = input.lines().collect_vec();
-    let times = lines[0].split_whitespace().skip(1);
-    let distances = lines[1].split_whitespace().skip(1);
-    times
-        .zip(distances)
-        .map(|(t, d)| Race {
-            duration: t.parse().unwrap(),
-            record_distance: d.parse().unwrap(),
+/// Races of the sheet, and the single race read when ignoring the spaces
+#[derive(Debug)]
+pub struct Sheet {
+    races: Vec<Race>,
+    kerned: Race,
+}
+
+/// Returns the `Time:` and `Distance:` lines along with their values
+fn parse_lines(input: &str) -> Result<(Line<'_>, Line<'_>), ParseError> {
+    let mut lines = utils::lines(DAY, input);
+    let mut next_line = |prefix: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| utils::unexpected_end(DAY, input, format!("`{prefix} <values>`")))?;
+        if line.text.starts_with(prefix) {
+            Ok(line)
+        } else {
+            Err(line.error(format!("`{prefix} <values>`")))
+        }
+    };
+    Ok((next_line("Time:")?, next_line("Distance:")?))
+}
+
+fn parse_input(input: &str) -> Result<Sheet, ParseError> {
+    let (times, distances) = parse_lines(input)?;
+    let parse_values = |line: &Line<'_>| {
+        line.text
+            .split_whitespace()
+            .skip(1)
+            .map(|v| line.parse::<u64>(v, "an integer"))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let (durations, record_distances) = (parse_values(&times)?, parse_values(&distances)?);
+    if durations.len() != record_distances.len() {
+        return Err(distances.error(format!("{} distances", durations.len())));
+    }
+    let races = durations
+        .into_iter()
+        .zip(record_distances)
+        .map(|(duration, record_distance)| Race {
+            duration,
+            record_distance,
         })
-        .collect()
+        .collect();
+
+    let parse_kerned = |line: &Line<'_>| {
+        let value = line.text.split_whitespace().skip(1).join("");
+        value
+            .parse::<u64>()
+            .map_err(|_| line.error("an integer split over several columns"))
+    };
+    let kerned = Race {
+        duration: parse_kerned(&times)?,
+        record_distance: parse_kerned(&distances)?,
+    };
+    Ok(Sheet { races, kerned })
 }
 
 fn get_improvement_possibilities(race: &Race) -> u64 {
@@ -33,40 +86,42 @@ fn get_improvement_possibilities(race: &Race) -> u64 {
     end - start + 1
 }
 
-pub fn part1(input: &str) -> u64 {
-    let races = parse_input(input);
-    races.iter().map(get_improvement_possibilities).product()
+fn part1(sheet: &Sheet) -> u64 {
+    sheet
+        .races
+        .iter()
+        .map(get_improvement_possibilities)
+        .product()
 }
 
-fn parse_input_part_2(input: &str) -> Race {
-    let lines = input.lines().collect_vec();
-    let time: u64 = lines[0]
-        .split_whitespace()
-        .skip(1)
-        .join("")
-        .parse()
-        .unwrap();
-    let distance: u64 = lines[1]
-        .split_whitespace()
-        .skip(1)
-        .join("")
-        .parse()
-        .unwrap();
-    Race {
-        duration: time,
-        record_distance: distance,
-    }
+fn part2(sheet: &Sheet) -> u64 {
+    get_improvement_possibilities(&sheet.kerned)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let race = parse_input_part_2(input);
-    get_improvement_possibilities(&race)
-}
+pub struct Day6;
+
+impl Day for Day6 {
+    type Parsed<'a> = Sheet;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+    fn title(&self) -> &'static str {
+        "Wait For It"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, sheet: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(sheet).into())
+    }
+
+    fn part2(&self, sheet: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(sheet).into())
+    }
 }
 
 #[cfg(test)]
@@ -80,7 +135,7 @@ mod test {
         Distance:  9  40  200
         "};
         let expected = 288;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(parse_input(input).map(|sheet| part1(&sheet)), Ok(expected));
     }
 
     #[test]
@@ -90,21 +145,6 @@ mod test {
         Distance:  9  40  200
         "};
         let expected = 71503;
-        assert_eq!(part2(&input), expected);
-    }
-
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+        assert_eq!(parse_input(input).map(|sheet| part2(&sheet)), Ok(expected));
     }
 }
@@ -2,9 +2,16 @@ use int_enum::IntEnum;
 use itertools::Itertools;
 use strum::EnumString;
 
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, ParseError},
+};
+
+const DAY: u32 = 7;
+
 #[derive(Debug, Copy, Clone, EnumString, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u8)]
-enum Card {
+pub enum Card {
     A = 14,
     K = 13,
     Q = 12,
@@ -29,7 +36,7 @@ enum Card {
     WeakJ = 1,
 }
 
-type Hand = [Card; 5];
+pub type Hand = [Card; 5];
 
 #[derive(Debug, Copy, Clone, IntEnum, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u8)]
@@ -95,25 +102,30 @@ impl Ord for HandWithCombination {
     }
 }
 
-fn parse_input(input: &str) -> Vec<(Hand, u64)> {
-    let lines = input.lines().collect_vec();
-    lines
-        .iter()
+fn parse_input(input: &str) -> Result<Vec<(Hand, u64)>, ParseError> {
+    utils::lines(DAY, input)
         .map(|line| {
-            let (hand_str, bid_str) = line.split_once(' ').unwrap();
+            let (hand_str, bid_str) = line
+                .text
+                .split_once(' ')
+                .ok_or_else(|| line.error("`<hand> <bid>`"))?;
+            if hand_str.chars().count() != 5 {
+                return Err(line.error_at(hand_str, "a hand of 5 cards"));
+            }
             let mut hand = [Card::A; 5];
-            for (i, card) in hand_str.char_indices() {
-                hand[i] = card.to_string().parse().unwrap();
+            for (i, (pos, card)) in hand_str.char_indices().enumerate() {
+                let card_str = &hand_str[pos..pos + card.len_utf8()];
+                hand[i] = line.parse(card_str, "a card among `AKQJT98765432`")?;
             }
-            (hand, bid_str.parse().unwrap())
+            Ok((hand, line.parse(bid_str, "a bid")?))
         })
         .collect()
 }
 
-pub fn part1(input: &str) -> u64 {
-    let mut hands_with_bid = parse_input(input)
-        .into_iter()
-        .map(|(hand, bid)| (HandWithCombination::from(hand), bid))
+fn part1(hands: &[(Hand, u64)]) -> u64 {
+    let mut hands_with_bid = hands
+        .iter()
+        .map(|&(hand, bid)| (HandWithCombination::from(hand), bid))
         .collect_vec();
     hands_with_bid.sort_by_key(|(hand, _)| *hand);
     hands_with_bid
@@ -159,10 +171,10 @@ impl HandWithCombination {
     }
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut hands_with_bid = parse_input(input)
-        .into_iter()
-        .map(|(hand, bid)| {
+fn part2(hands: &[(Hand, u64)]) -> u64 {
+    let mut hands_with_bid = hands
+        .iter()
+        .map(|&(hand, bid)| {
             (
                 HandWithCombination::from_hand_with_jokers(hand.map(|c| {
                     if c == Card::J {
@@ -183,10 +195,30 @@ pub fn part2(input: &str) -> u64 {
         .sum()
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day7;
+
+impl Day for Day7 {
+    type Parsed<'a> = Vec<(Hand, u64)>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Camel Cards"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, hands: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(hands).into())
+    }
+
+    fn part2(&self, hands: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(hands).into())
+    }
 }
 
 #[cfg(test)]
@@ -203,7 +235,7 @@ mod test {
         QQQJA 483
         "};
         let expected = 6440;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(parse_input(input).map(|hands| part1(&hands)), Ok(expected));
     }
 
     #[test]
@@ -216,21 +248,6 @@ mod test {
         QQQJA 483
         "};
         let expected = 5905;
-        assert_eq!(part2(&input), expected);
-    }
-
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+        assert_eq!(parse_input(input).map(|hands| part2(&hands)), Ok(expected));
     }
 }
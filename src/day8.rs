@@ -1,118 +1,371 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
-use num::integer;
-use parse_display::FromStr;
+use num::Integer;
+use petgraph::{
+    dot::Dot,
+    graph::{DiGraph, EdgeReference, NodeIndex},
+    visit::EdgeRef,
+};
 
-type NodeId = [char; 3];
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, geom::Turn, math, Line, ParseError},
+};
 
-struct Node {
+const DAY: u32 = 8;
+
+pub type NodeId = [char; 3];
+
+pub struct Node {
     id: NodeId,
     children: (NodeId, NodeId),
 }
 
-#[derive(Debug, FromStr)]
-enum Direction {
-    #[display("L")]
-    Left,
-    #[display("R")]
-    Right,
+fn parse_node_id(line: &Line<'_>, s: &str) -> Result<NodeId, ParseError> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some(a), Some(b), Some(c), None) => Ok([a, b, c]),
+        _ => Err(line.error_at(s, "a 3 characters node id")),
+    }
 }
 
-fn parse_input(input: &str) -> (Vec<Direction>, HashMap<NodeId, Node>) {
-    let mut lines = input.lines();
-    let directions: Vec<Direction> = lines
+fn parse_input(input: &str) -> Result<(Vec<Turn>, HashMap<NodeId, Node>), ParseError> {
+    let mut lines = utils::lines(DAY, input);
+    let directions_line = lines
         .next()
-        .unwrap()
-        .chars()
-        .map(|c| c.to_string().parse().unwrap())
-        .collect();
-    lines.next();
-    let nodes = lines
+        .ok_or_else(|| utils::unexpected_end(DAY, input, "a list of `L`/`R` directions"))?;
+    let directions: Vec<Turn> = directions_line
+        .text
+        .char_indices()
+        .map(|(i, c)| {
+            directions_line.parse(&directions_line.text[i..i + c.len_utf8()], "`L` or `R`")
+        })
+        .collect::<Result<_, _>>()?;
+    if directions.is_empty() {
+        return Err(directions_line.error("at least one direction"));
+    }
+    if let Some(separator) = lines.next().filter(|line| !line.text.is_empty()) {
+        return Err(separator.error("an empty line"));
+    }
+    let parsed_nodes = lines
         .map(|line| {
-            let mut chars = line.chars();
-            let id = [
-                chars.next().unwrap(),
-                chars.next().unwrap(),
-                chars.next().unwrap(),
-            ];
-            let mut chars = chars.skip_while(|c| *c != '(');
-            chars.next();
-            let l_child = [
-                chars.next().unwrap(),
-                chars.next().unwrap(),
-                chars.next().unwrap(),
-            ];
-            let mut chars = chars.skip_while(|c| *c != ' ');
-            chars.next();
-            let r_child = [
-                chars.next().unwrap(),
-                chars.next().unwrap(),
-                chars.next().unwrap(),
-            ];
-            (
-                id,
-                Node {
-                    id,
-                    children: (l_child, r_child),
-                },
-            )
+            let (id_str, children_str) = line
+                .text
+                .split_once(" = ")
+                .ok_or_else(|| line.error("`<node> = (<left>, <right>)`"))?;
+            let (l_str, r_str) = children_str
+                .strip_prefix('(')
+                .and_then(|s| s.strip_suffix(')'))
+                .and_then(|s| s.split_once(", "))
+                .ok_or_else(|| line.error_at(children_str, "`(<left>, <right>)`"))?;
+            let id = parse_node_id(&line, id_str)?;
+            let children = (parse_node_id(&line, l_str)?, parse_node_id(&line, r_str)?);
+            Ok((line, [l_str, r_str], Node { id, children }))
         })
-        .collect::<HashMap<NodeId, Node>>();
+        .collect::<Result<Vec<_>, ParseError>>()?;
+    let ids = parsed_nodes
+        .iter()
+        .map(|(_, _, node)| node.id)
+        .collect::<HashSet<_>>();
+    // Make sure the walks never reach an undefined node
+    for (line, children_strs, node) in parsed_nodes.iter() {
+        for (child, child_str) in [node.children.0, node.children.1].iter().zip(children_strs) {
+            if !ids.contains(child) {
+                return Err(line.error_at(child_str, "a defined node"));
+            }
+        }
+    }
+    let nodes = parsed_nodes
+        .into_iter()
+        .map(|(_, _, node)| (node.id, node))
+        .collect();
 
-    (directions, nodes)
+    Ok((directions, nodes))
 }
 
-pub fn part1(input: &str) -> u64 {
-    let (directions, nodes) = parse_input(input);
+/// Steps from `AAA` to `ZZZ`, if both exist and the walk reaches `ZZZ`
+fn part1((directions, nodes): &(Vec<Turn>, HashMap<NodeId, Node>)) -> Option<u64> {
     let mut current = ['A', 'A', 'A'];
     const END_NODE_ID: NodeId = ['Z', 'Z', 'Z'];
-    for (i, direction) in directions.iter().cycle().enumerate() {
+    if !nodes.contains_key(&current) || !nodes.contains_key(&END_NODE_ID) {
+        return None;
+    }
+    // Past this many steps, the walk is back on a node at the same point of
+    // the directions, so it loops without reaching `ZZZ`
+    let max_steps = nodes.len() * directions.len();
+    for (i, direction) in directions.iter().cycle().take(max_steps).enumerate() {
         let node = &nodes[&current];
         match direction {
-            Direction::Left => current = node.children.0,
-            Direction::Right => current = node.children.1,
+            Turn::Left => current = node.children.0,
+            Turn::Right => current = node.children.1,
         }
         if current == END_NODE_ID {
-            return i as u64 + 1;
+            return Some(i as u64 + 1);
         }
     }
-    unreachable!()
+    None
 }
 
-pub fn part2(input: &str) -> u64 {
-    let (directions, nodes) = parse_input(input);
-    let mut current_nodes = nodes.values().filter(|n| n.id[2] == 'A').collect_vec();
-    let dist_to_z = current_nodes
-        .iter()
-        .map(|n| {
-            let mut current = *n;
-            for (i, direction) in directions.iter().cycle().enumerate() {
-                match direction {
-                    Direction::Left => current = &nodes[&current.children.0],
-                    Direction::Right => current = &nodes[&current.children.1],
-                }
-                if current.id[2] == 'Z' {
-                    return i as u64 + 1;
-                }
+/// Node reached by following one direction from `id`
+fn next_node(nodes: &HashMap<NodeId, Node>, id: NodeId, direction: Turn) -> NodeId {
+    let node = &nodes[&id];
+    match direction {
+        Turn::Left => node.children.0,
+        Turn::Right => node.children.1,
+    }
+}
+
+/// Walk of a ghost, which loops once it is back on a node at the same point
+/// of the directions
+#[derive(Debug, PartialEq, Eq)]
+struct Cycle {
+    /// Steps before entering the loop
+    start: u64,
+    /// Node on which the loop starts
+    entry: NodeId,
+    len: u64,
+    /// Steps at which the ghost is on a `..Z` node before entering the loop
+    prefix_ends: Vec<u64>,
+    /// Steps of the first round of the loop at which the ghost is on a `..Z` node
+    cycle_ends: Vec<u64>,
+}
+
+impl Cycle {
+    fn find(directions: &[Turn], nodes: &HashMap<NodeId, Node>, start: NodeId) -> Self {
+        let mut seen = HashMap::new();
+        let mut ends = Vec::new();
+        let mut current = start;
+        for step in 0u64.. {
+            let instruction = (step % directions.len() as u64) as usize;
+            if let Some(&first) = seen.get(&(current, instruction)) {
+                let (prefix_ends, cycle_ends) = ends.into_iter().partition(|end| *end < first);
+                return Self {
+                    start: first,
+                    entry: current,
+                    len: step - first,
+                    prefix_ends,
+                    cycle_ends,
+                };
             }
-            unreachable!()
-        })
+            seen.insert((current, instruction), step);
+            if current[2] == 'Z' {
+                ends.push(step);
+            }
+            current = next_node(nodes, current, directions[instruction]);
+        }
+        unreachable!()
+    }
+
+    /// Node and direction followed at each step of the loop
+    fn loop_edges(
+        &self,
+        directions: &[Turn],
+        nodes: &HashMap<NodeId, Node>,
+    ) -> Vec<(NodeId, Turn)> {
+        let mut current = self.entry;
+        (self.start..self.start + self.len)
+            .map(|step| {
+                let direction = directions[(step % directions.len() as u64) as usize];
+                let edge = (current, direction);
+                current = next_node(nodes, current, direction);
+                edge
+            })
+            .collect()
+    }
+
+    fn is_end(&self, step: u64) -> bool {
+        if step < self.start {
+            self.prefix_ends.binary_search(&step).is_ok()
+        } else {
+            let step = self.start + (step - self.start) % self.len;
+            self.cycle_ends.binary_search(&step).is_ok()
+        }
+    }
+}
+
+/// Most combinations of loop ends solved with the CRT, past which part 2
+/// walks the loops instead
+const MAX_COMBINATIONS: usize = 1 << 12;
+
+/// First step at which every ghost is on a `..Z` node, if any
+fn part2((directions, nodes): &(Vec<Turn>, HashMap<NodeId, Node>)) -> Option<u64> {
+    let cycles = nodes
+        .keys()
+        .filter(|id| id[2] == 'A')
+        .map(|id| Cycle::find(directions, nodes, *id))
         .collect_vec();
-    let lcm = dist_to_z.iter().fold(1, |acc, x| integer::lcm(acc, *x));
-    lcm
+    let looping_from = cycles.iter().map(|cycle| cycle.start).max()?.max(1);
+    // Simulate until every ghost is in its loop
+    if let Some(step) = (1..looping_from).find(|step| cycles.iter().all(|c| c.is_end(*step))) {
+        return Some(step);
+    }
+    let combinations = cycles
+        .iter()
+        .try_fold(1usize, |n, cycle| n.checked_mul(cycle.cycle_ends.len()));
+    if combinations.is_some_and(|n| n <= MAX_COMBINATIONS) {
+        solve_congruences(&cycles, looping_from)
+    } else {
+        walk_loops(&cycles, looping_from)
+    }
+}
+
+/// First step from `from` at which every ghost is on a `..Z` node, once they
+/// are all in their loop
+///
+/// Each ghost is then on an end at the steps congruent to one of its loop
+/// ends, so this tries every combination of them.
+fn solve_congruences(cycles: &[Cycle], from: u64) -> Option<u64> {
+    let from = from as i128;
+    cycles
+        .iter()
+        .map(|cycle| {
+            cycle
+                .cycle_ends
+                .iter()
+                .map(|end| (*end as i128, cycle.len as i128))
+                .collect_vec()
+        })
+        .multi_cartesian_product()
+        .filter_map(|congruences| congruences.into_iter().try_fold((0, 1), math::crt))
+        .filter_map(|(step, modulus)| {
+            let rounds = Integer::div_ceil(&(from - step).max(0), &modulus);
+            step.checked_add(rounds.checked_mul(modulus)?)
+        })
+        .min()
+        .and_then(|step| u64::try_from(step).ok())
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+/// Same as [`solve_congruences`], walking the ends of the ghost with the
+/// sparsest ones until the loops all repeat
+fn walk_loops(cycles: &[Cycle], from: u64) -> Option<u64> {
+    let sparsest = cycles.iter().min_by(|a, b| {
+        let density = |c: &Cycle, other: &Cycle| c.cycle_ends.len() as u128 * other.len as u128;
+        density(a, b).cmp(&density(b, a))
+    })?;
+    if sparsest.cycle_ends.is_empty() {
+        return None;
+    }
+    let period = cycles.iter().try_fold(1u64, |period, cycle| {
+        (period / period.gcd(&cycle.len)).checked_mul(cycle.len)
+    });
+    let until = period
+        .and_then(|period| from.checked_add(period))
+        .unwrap_or(u64::MAX);
+    (0u64..)
+        .map_while(|round| round.checked_mul(sparsest.len))
+        .flat_map(|offset| {
+            sparsest
+                .cycle_ends
+                .iter()
+                .filter_map(move |end| end.checked_add(offset))
+        })
+        .skip_while(|step| *step < from)
+        .take_while(|step| *step < until)
+        .find(|step| cycles.iter().all(|cycle| cycle.is_end(*step)))
+}
+
+/// Network as a graph, with an edge to each child weighted by the direction
+/// leading to it
+pub fn to_graph(nodes: &HashMap<NodeId, Node>) -> DiGraph<NodeId, Turn> {
+    let mut graph = DiGraph::new();
+    let indices = nodes
+        .keys()
+        .sorted()
+        .map(|id| (*id, graph.add_node(*id)))
+        .collect::<HashMap<_, _>>();
+    for id in nodes.keys().sorted() {
+        let (left, right) = nodes[id].children;
+        graph.add_edge(indices[id], indices[&left], Turn::Left);
+        graph.add_edge(indices[id], indices[&right], Turn::Right);
+    }
+    graph
+}
+
+/// Graphviz rendering of the network, filling the start and end nodes and
+/// colouring the loop each ghost ends up in
+pub fn to_dot((directions, nodes): &(Vec<Turn>, HashMap<NodeId, Node>)) -> String {
+    const LOOP_COLORS: [&str; 6] = ["blue", "orange", "purple", "brown", "darkgreen", "magenta"];
+    let graph = to_graph(nodes).map(|_, id| id.iter().collect::<String>(), |_, turn| *turn);
+    let indices = graph
+        .node_indices()
+        .map(|i| (graph[i].clone(), i))
+        .collect::<HashMap<_, _>>();
+    let mut loop_colors = HashMap::new();
+    let starts = nodes.keys().filter(|id| id[2] == 'A').sorted();
+    for (ghost, start) in starts.enumerate() {
+        let cycle = Cycle::find(directions, nodes, *start);
+        for (id, turn) in cycle.loop_edges(directions, nodes) {
+            let source = indices[&id.iter().collect::<String>()];
+            loop_colors.insert((source, turn), LOOP_COLORS[ghost % LOOP_COLORS.len()]);
+        }
+    }
+    let node_attributes = |_, (_, id): (NodeIndex, &String)| match id.chars().last() {
+        Some('A') => "style = filled fillcolor = palegreen".to_string(),
+        Some('Z') => "style = filled fillcolor = lightpink".to_string(),
+        _ => String::new(),
+    };
+    let edge_attributes = |_, edge: EdgeReference<'_, Turn>| {
+        loop_colors
+            .get(&(edge.source(), *edge.weight()))
+            .map_or(String::new(), |color| {
+                format!("color = {} penwidth = 2", color)
+            })
+    };
+    Dot::with_attr_getters(&graph, &[], &edge_attributes, &node_attributes).to_string()
+}
+
+pub struct Day8;
+
+impl Day for Day8 {
+    type Parsed<'a> = (Vec<Turn>, HashMap<NodeId, Node>);
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, network: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let steps = part1(network).ok_or_else(|| SolveError::NoAnswer {
+            day: DAY,
+            reason: "the walk from `AAA` never reaches `ZZZ`".into(),
+        })?;
+        Ok(steps.into())
+    }
+
+    fn part2(&self, network: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let steps = part2(network).ok_or_else(|| SolveError::NoAnswer {
+            day: DAY,
+            reason: "the ghosts are never all on a `..Z` node at once".into(),
+        })?;
+        Ok(steps.into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    const SAMPLE_PART2: &str = crate::utils::sample_input! {"
+    LR
+
+    11A = (11B, XXX)
+    11B = (XXX, 11Z)
+    11Z = (11B, XXX)
+    22A = (22B, XXX)
+    22B = (22C, 22C)
+    22C = (22Z, 22Z)
+    22Z = (22B, 22B)
+    XXX = (XXX, XXX)
+    "};
+
     #[test]
     fn test_part1() {
         let input = crate::utils::sample_input! {"
@@ -122,40 +375,136 @@ mod test {
         BBB = (AAA, ZZZ)
         ZZZ = (ZZZ, ZZZ)
         "};
-        let expected = 6;
-        assert_eq!(part1(&input), expected);
+        let expected = Some(6);
+        assert_eq!(
+            parse_input(input).map(|network| part1(&network)),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_part1_unreachable() {
+        let missing_end = "L\n\nAAA = (AAA, AAA)\n";
+        assert_eq!(parse_input(missing_end).map(|n| part1(&n)), Ok(None));
+        let looping = "L\n\nAAA = (AAA, AAA)\nZZZ = (AAA, AAA)\n";
+        assert_eq!(parse_input(looping).map(|n| part1(&n)), Ok(None));
+        assert_eq!(
+            Day8.part1(&parse_input(looping).unwrap())
+                .unwrap_err()
+                .to_string(),
+            "day8: no answer, the walk from `AAA` never reaches `ZZZ`"
+        );
+    }
+
+    #[test]
+    fn test_missing_separator() {
+        let input = "L\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        assert_eq!(
+            parse_input(input).err().map(|e| e.to_string()),
+            Some("day8 line 2: expected an empty line".to_string())
+        );
     }
 
     #[test]
     fn test_part2() {
+        let expected = Some(6);
+        assert_eq!(
+            parse_input(SAMPLE_PART2).map(|network| part2(&network)),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_part2_offset_cycles() {
+        // Ends are reached at steps 3 + 4k and 2 + 3k, which a plain LCM misses
         let input = crate::utils::sample_input! {"
-        LR
-
-        11A = (11B, XXX)
-        11B = (XXX, 11Z)
-        11Z = (11B, XXX)
-        22A = (22B, XXX)
-        22B = (22C, 22C)
-        22C = (22Z, 22Z)
-        22Z = (22B, 22B)
-        XXX = (XXX, XXX)
+        L
+
+        11A = (11X, 11X)
+        11X = (11Y, 11Y)
+        11Y = (11Z, 11Z)
+        11Z = (11W, 11W)
+        11W = (11X, 11X)
+        22A = (22P, 22P)
+        22P = (22Z, 22Z)
+        22Z = (22Q, 22Q)
+        22Q = (22P, 22P)
         "};
-        let expected = 6;
-        assert_eq!(part2(&input), expected);
+        let network = parse_input(input).unwrap();
+        let cycle = Cycle::find(&network.0, &network.1, ['1', '1', 'A']);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 1,
+                entry: ['1', '1', 'X'],
+                len: 4,
+                prefix_ends: vec![],
+                cycle_ends: vec![3],
+            }
+        );
+        assert_eq!(part2(&network), Some(11));
     }
 
-    extern crate test;
-    use test::test::Bencher;
+    #[test]
+    fn test_part2_ends_before_cycles() {
+        let input = crate::utils::sample_input! {"
+        L
+
+        11A = (11Z, 11Z)
+        11Z = (11B, 11B)
+        11B = (11B, 11B)
+        22A = (22Z, 22Z)
+        22Z = (22Z, 22Z)
+        33A = (33B, 33B)
+        33B = (33Z, 33Z)
+        33Z = (33Z, 33Z)
+        "};
+        let network = parse_input(input).unwrap();
+        let cycle = Cycle::find(&network.0, &network.1, ['1', '1', 'A']);
+        assert_eq!(cycle.prefix_ends, vec![1]);
+        assert!(cycle.cycle_ends.is_empty());
+        let without_33 = (
+            network.0.clone(),
+            network
+                .1
+                .into_iter()
+                .filter(|(id, _)| id[0] != '3')
+                .collect(),
+        );
+        assert_eq!(part2(&without_33), Some(1));
+        assert!(Day8.part2(&parse_input(input).unwrap()).is_err());
+    }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
+    #[test]
+    fn test_part2_many_ghosts() {
+        // 13 ghosts on odd steps and one on steps 2 + 3k, too many
+        // combinations of ends for the CRT
+        let cycle = |len, cycle_ends| Cycle {
+            start: 0,
+            entry: ['0', '0', '0'],
+            len,
+            prefix_ends: vec![],
+            cycle_ends,
+        };
+        let mut cycles = (0..13).map(|_| cycle(4, vec![1, 3])).collect_vec();
+        cycles.push(cycle(3, vec![2]));
+        assert_eq!(walk_loops(&cycles, 1), Some(5));
+        assert_eq!(solve_congruences(&cycles, 1), Some(5));
+        cycles.push(cycle(2, vec![0]));
+        assert_eq!(walk_loops(&cycles, 1), None);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+    #[test]
+    fn test_graph_and_dot() {
+        let network = parse_input(SAMPLE_PART2).unwrap();
+        let graph = to_graph(&network.1);
+        assert_eq!((graph.node_count(), graph.edge_count()), (8, 16));
+        let dot = to_dot(&network);
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains(r#"0 [ label = "11A" style = filled fillcolor = palegreen]"#));
+        assert!(dot.contains(r#"6 [ label = "22Z" style = filled fillcolor = lightpink]"#));
+        assert!(dot.contains(r#"1 -> 2 [ label = "R" color = blue penwidth = 2]"#));
+        assert!(dot.contains(r#"6 -> 4 [ label = "L" color = orange penwidth = 2]"#));
+        assert!(dot.contains(r#"0 -> 7 [ label = "R" ]"#));
     }
 }
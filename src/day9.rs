@@ -1,17 +1,26 @@
 use std::iter;
 
-use itertools::Itertools;
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, ParseError},
+};
 
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    let lines = input.lines().collect_vec();
-    lines
-        .iter()
+const DAY: u32 = 9;
+
+fn parse_input(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    utils::lines(DAY, input)
         .map(|line| {
-            line.split_whitespace()
-                .map(|s| s.parse::<i32>().unwrap())
-                .collect_vec()
+            let sequence = line
+                .text
+                .split_whitespace()
+                .map(|s| line.parse::<i32>(s, "an integer"))
+                .collect::<Result<Vec<_>, _>>()?;
+            if sequence.is_empty() {
+                return Err(line.error("a sequence of integers"));
+            }
+            Ok(sequence)
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
 fn derive(input: &[i32]) -> Vec<i32> {
@@ -34,15 +43,12 @@ fn integrate(input: &[i32], c: i32) -> Vec<i32> {
         .collect()
 }
 
-fn extrapolate(input: &Vec<i32>) -> i32 {
-    let mut seq = input.clone();
+fn extrapolate(input: &[i32]) -> i32 {
+    let mut seq = input.to_vec();
     let mut first_stack = Vec::new();
-    loop {
-        let first = seq.first().unwrap();
-        if seq.iter().all(|x| *x == 0) {
-            break;
-        }
-        first_stack.push(*first);
+    // A single value derives into an empty sequence, which is all zeros too
+    while !seq.iter().all(|x| *x == 0) {
+        first_stack.push(seq[0]);
         seq = derive(&seq);
     }
     seq.push(0);
@@ -52,23 +58,45 @@ fn extrapolate(input: &Vec<i32>) -> i32 {
     *seq.last().unwrap()
 }
 
-pub fn part1(input: &str) -> i32 {
-    let sequences = parse_input(input);
-    sequences.iter().map(extrapolate).sum()
+fn part1(sequences: &[Vec<i32>]) -> i32 {
+    sequences.iter().map(|s| extrapolate(s)).sum()
 }
 
-pub fn part2(input: &str) -> i32 {
-    let mut sequences = parse_input(input);
-    for sequence in sequences.iter_mut() {
-        sequence.reverse();
-    }
-    sequences.iter().map(extrapolate).sum()
+fn part2(sequences: &[Vec<i32>]) -> i32 {
+    sequences
+        .iter()
+        .map(|s| {
+            let mut reversed = s.clone();
+            reversed.reverse();
+            extrapolate(&reversed)
+        })
+        .sum()
 }
 
-fn main() {
-    let lines = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+pub struct Day9;
+
+impl Day for Day9 {
+    type Parsed<'a> = Vec<Vec<i32>>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "Mirage Maintenance"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, sequences: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(sequences).into())
+    }
+
+    fn part2(&self, sequences: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(sequences).into())
+    }
 }
 
 #[cfg(test)]
@@ -83,7 +111,10 @@ mod test {
         10 13 16 21 30 45
         "};
         let expected = 114;
-        assert_eq!(part1(&input), expected);
+        assert_eq!(
+            parse_input(input).map(|sequences| part1(&sequences)),
+            Ok(expected)
+        );
     }
 
     #[test]
@@ -94,21 +125,18 @@ mod test {
         10 13 16 21 30 45
         "};
         let expected = 2;
-        assert_eq!(part2(&input), expected);
+        assert_eq!(
+            parse_input(input).map(|sequences| part2(&sequences)),
+            Ok(expected)
+        );
     }
 
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part1(&lines));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let lines = crate::utils::get_day_input!();
-        b.iter(|| part2(&lines));
+    #[test]
+    fn test_single_value() {
+        assert_eq!(extrapolate(&[5]), 5);
+        assert_eq!(
+            parse_input("7\n0\n").map(|s| (part1(&s), part2(&s))),
+            Ok((7, 7))
+        );
     }
 }
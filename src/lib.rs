@@ -1,6 +1,13 @@
-#![feature(test)]
-
-mod utils;
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod memory;
+pub mod render;
+pub mod samples;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod utils;
 
 pub mod day1;
 pub mod day10;
@@ -12,3 +19,33 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+use solution::Solution;
+
+/// Every solved day, ordered by day number
+pub static DAYS: &[&dyn Solution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+];
+
+pub fn get_day(day: u32) -> Option<&'static dyn Solution> {
+    DAYS.iter().find(|s| s.day() == day).copied()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_days_are_ordered_and_unique() {
+        assert!(DAYS.windows(2).all(|w| w[0].day() < w[1].day()));
+    }
+}
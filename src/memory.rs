@@ -0,0 +1,107 @@
+//! Allocation tracking, only counting with the `alloc-stats` feature
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    pub static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    pub static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    /// System allocator keeping track of the allocated bytes
+    ///
+    /// It only counts once installed with `#[global_allocator]`.
+    pub struct CountingAllocator;
+
+    fn allocated(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn deallocated(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            deallocated(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                deallocated(layout.size());
+                allocated(new_size);
+            }
+            new_ptr
+        }
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+pub use counting::CountingAllocator;
+
+/// Number of bytes currently allocated
+pub fn allocated() -> Option<usize> {
+    #[cfg(feature = "alloc-stats")]
+    return Some(counting::CURRENT.load(std::sync::atomic::Ordering::Relaxed));
+    #[cfg(not(feature = "alloc-stats"))]
+    None
+}
+
+/// Runs `f`, returning the peak number of bytes it had allocated at once
+///
+/// Allocations made meanwhile by other threads are counted too.
+pub fn peak_allocated<T>(f: impl FnOnce() -> T) -> (T, Option<usize>) {
+    #[cfg(feature = "alloc-stats")]
+    {
+        use std::sync::atomic::Ordering;
+
+        let start = counting::CURRENT.load(Ordering::Relaxed);
+        counting::PEAK.store(start, Ordering::Relaxed);
+        let result = f();
+        let peak = counting::PEAK.load(Ordering::Relaxed).saturating_sub(start);
+        (result, Some(peak))
+    }
+    #[cfg(not(feature = "alloc-stats"))]
+    (f(), None)
+}
+
+#[cfg(all(test, feature = "alloc-stats"))]
+mod test {
+    use std::hint::black_box;
+
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_peak_allocated() {
+        let (len, peak) = peak_allocated(|| {
+            let buffer = black_box(vec![0u8; 1 << 20]);
+            buffer.len()
+        });
+        assert_eq!(len, 1 << 20);
+        assert!(peak.unwrap() >= 1 << 20);
+    }
+}
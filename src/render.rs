@@ -0,0 +1,339 @@
+//! Pictures of grid puzzles, written as SVG or, with the `png` feature, PNG
+
+use std::{
+    fmt,
+    fmt::Write as _,
+    fs, io,
+    ops::{Index, IndexMut},
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{
+    geom::{Direction, Vec2},
+    Grid, Pos,
+};
+
+/// Side of a cell, in pixels
+const CELL_SIZE: usize = 16;
+/// Width of the lines drawn in cells, in pixels
+const LINE_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Self = Self(255, 255, 255);
+}
+
+/// Displays the colour as `#rrggbb`
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Look of a cell of a picture, blank by default
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cell {
+    pub background: Option<Rgb>,
+    /// Sides of the cell joined to its centre by a line, to draw pipes
+    pub lines: Vec<Direction>,
+    pub line_color: Rgb,
+    /// Character written in the cell, only drawn in SVG
+    pub label: Option<char>,
+    pub label_color: Rgb,
+}
+
+/// Image format of a picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    /// Guesses the format of a picture from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => Some(Self::Svg),
+            Some("png") => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Error raised when saving a picture
+#[derive(Debug)]
+pub enum RenderError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    UnknownFormat(PathBuf),
+    /// PNG pictures need the `png` feature
+    PngUnsupported,
+    #[cfg(feature = "png")]
+    Png(png::EncodingError),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::UnknownFormat(path) => {
+                write!(f, "{}: expected a `.svg` or `.png` file", path.display())
+            }
+            Self::PngUnsupported => write!(f, "PNG pictures need the `png` feature"),
+            #[cfg(feature = "png")]
+            Self::Png(e) => write!(f, "cannot encode PNG: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::UnknownFormat(_) | Self::PngUnsupported => None,
+            #[cfg(feature = "png")]
+            Self::Png(e) => Some(e),
+        }
+    }
+}
+
+/// Picture made of square cells, one per cell of a puzzle grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    cells: Grid<Cell>,
+}
+
+impl Picture {
+    /// Blank picture of `width` by `height` cells
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: Grid::new(width, height, Cell::default()),
+        }
+    }
+
+    /// Size in pixels
+    pub fn size(&self) -> (usize, usize) {
+        (
+            self.cells.width() * CELL_SIZE,
+            self.cells.height() * CELL_SIZE,
+        )
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size();
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            Rgb::WHITE
+        )
+        .unwrap();
+        for (pos, cell) in self.cells.iter() {
+            let (x, y) = (pos.col * CELL_SIZE, pos.row * CELL_SIZE);
+            if let Some(background) = cell.background {
+                writeln!(
+                    svg,
+                    r#"<rect x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}" fill="{background}"/>"#
+                )
+                .unwrap();
+            }
+            if !cell.lines.is_empty() {
+                let half = CELL_SIZE / 2;
+                let (cx, cy) = (x + half, y + half);
+                let path = cell
+                    .lines
+                    .iter()
+                    .map(|direction| {
+                        let end = Vec2::from(*direction) * half as isize;
+                        let (ex, ey) = (cx as isize + end.col, cy as isize + end.row);
+                        format!("M{cx} {cy}L{ex} {ey}")
+                    })
+                    .collect::<String>();
+                writeln!(
+                    svg,
+                    r#"<path d="{path}" stroke="{}" stroke-width="{LINE_WIDTH}" stroke-linecap="square"/>"#,
+                    cell.line_color
+                )
+                .unwrap();
+            }
+            if let Some(label) = cell.label {
+                let label = match label {
+                    '&' => "&amp;".to_string(),
+                    '<' => "&lt;".to_string(),
+                    '>' => "&gt;".to_string(),
+                    c => c.to_string(),
+                };
+                writeln!(
+                    svg,
+                    r#"<text x="{}" y="{}" font-family="monospace" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="{}">{label}</text>"#,
+                    x + CELL_SIZE / 2,
+                    y + CELL_SIZE / 2,
+                    CELL_SIZE * 3 / 4,
+                    cell.label_color
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Pixels of the picture as RGB triplets, row by row, without the labels
+    pub fn to_pixels(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let mut pixels = vec![255; width * height * 3];
+        let mut fill = |x: usize, y: usize, w: usize, h: usize, color: Rgb| {
+            for row in y..y + h {
+                for col in x..x + w {
+                    let i = (row * width + col) * 3;
+                    pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+                }
+            }
+        };
+        let half = CELL_SIZE / 2;
+        let edge = half - LINE_WIDTH / 2;
+        for (pos, cell) in self.cells.iter() {
+            let (x, y) = (pos.col * CELL_SIZE, pos.row * CELL_SIZE);
+            if let Some(background) = cell.background {
+                fill(x, y, CELL_SIZE, CELL_SIZE, background);
+            }
+            if !cell.lines.is_empty() {
+                fill(x + edge, y + edge, LINE_WIDTH, LINE_WIDTH, cell.line_color);
+            }
+            for direction in &cell.lines {
+                let (x, y, w, h) = match direction {
+                    Direction::North => (x + edge, y, LINE_WIDTH, half),
+                    Direction::East => (x + half, y + edge, half, LINE_WIDTH),
+                    Direction::South => (x + edge, y + half, LINE_WIDTH, half),
+                    Direction::West => (x, y + edge, half, LINE_WIDTH),
+                };
+                fill(x, y, w, h, cell.line_color);
+            }
+        }
+        pixels
+    }
+
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>, RenderError> {
+        let (width, height) = self.size();
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(RenderError::Png)?;
+        writer
+            .write_image_data(&self.to_pixels())
+            .map_err(RenderError::Png)?;
+        writer.finish().map_err(RenderError::Png)?;
+        Ok(png)
+    }
+
+    /// Writes the picture in the format matching the extension of `path`
+    pub fn save(&self, path: &Path) -> Result<(), RenderError> {
+        let content = match ImageFormat::from_path(path) {
+            Some(ImageFormat::Svg) => self.to_svg().into_bytes(),
+            #[cfg(feature = "png")]
+            Some(ImageFormat::Png) => self.to_png()?,
+            #[cfg(not(feature = "png"))]
+            Some(ImageFormat::Png) => return Err(RenderError::PngUnsupported),
+            None => return Err(RenderError::UnknownFormat(path.to_path_buf())),
+        };
+        fs::write(path, content).map_err(|source| RenderError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+impl Index<Pos> for Picture {
+    type Output = Cell;
+
+    fn index(&self, pos: Pos) -> &Cell {
+        &self.cells[pos]
+    }
+}
+
+impl IndexMut<Pos> for Picture {
+    fn index_mut(&mut self, pos: Pos) -> &mut Cell {
+        &mut self.cells[pos]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn picture() -> Picture {
+        let mut picture = Picture::new(2, 1);
+        picture[Pos::new(0, 0)] = Cell {
+            background: Some(Rgb(255, 0, 0)),
+            lines: vec![Direction::East],
+            ..Cell::default()
+        };
+        picture[Pos::new(0, 1)].label = Some('<');
+        picture
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = picture().to_svg();
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="16""#)
+        );
+        assert!(svg.contains(r##"<rect x="0" y="0" width="16" height="16" fill="#ff0000"/>"##));
+        assert!(svg.contains(r##"<path d="M8 8L16 8" stroke="#000000""##));
+        assert!(svg.contains(">&lt;</text>"));
+        assert_eq!(Rgb(1, 171, 255).to_string(), "#01abff");
+    }
+
+    #[test]
+    fn test_pixels() {
+        let pixels = picture().to_pixels();
+        assert_eq!(pixels.len(), 32 * 16 * 3);
+        let pixel = |x: usize, y: usize| &pixels[(y * 32 + x) * 3..(y * 32 + x) * 3 + 3];
+        assert_eq!(pixel(0, 0), [255, 0, 0]);
+        assert_eq!(pixel(15, 8), [0, 0, 0]);
+        assert_eq!(pixel(4, 8), [255, 0, 0]);
+        assert_eq!(pixel(20, 8), [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_save() {
+        let dir = crate::utils::test::temp_dir("render");
+        picture().save(&dir.join("picture.svg")).unwrap();
+        assert!(fs::read_to_string(dir.join("picture.svg"))
+            .unwrap()
+            .ends_with("</svg>\n"));
+        assert!(matches!(
+            picture().save(&dir.join("picture.bmp")),
+            Err(RenderError::UnknownFormat(_))
+        ));
+        let error = picture()
+            .save(&dir.join("missing/picture.svg"))
+            .unwrap_err();
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(source.downcast_ref::<io::Error>().is_some());
+        #[cfg(feature = "png")]
+        {
+            picture().save(&dir.join("picture.png")).unwrap();
+            let png = fs::read(dir.join("picture.png")).unwrap();
+            let decoder = png::Decoder::new(png.as_slice());
+            let reader = decoder.read_info().unwrap();
+            assert_eq!((reader.info().width, reader.info().height), (32, 16));
+        }
+        #[cfg(not(feature = "png"))]
+        assert!(matches!(
+            picture().save(&dir.join("picture.png")),
+            Err(RenderError::PngUnsupported)
+        ));
+    }
+}
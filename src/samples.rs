@@ -0,0 +1,199 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Example given in a puzzle description, with its expected answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub part: u8,
+    pub input: String,
+    pub expected: Option<String>,
+}
+
+/// Directory where the sample fixtures are stored
+pub fn sample_dir(input_dir: &Path) -> PathBuf {
+    input_dir.join("samples")
+}
+
+/// Sample input fixture of a part, e.g. `inputs/samples/day05-1.txt`
+pub fn sample_path(sample_dir: &Path, day: u32, part: u8) -> PathBuf {
+    sample_dir.join(format!("day{:02}-{}.txt", day, part))
+}
+
+/// Expected answer fixture of a part, e.g. `inputs/samples/day05-1.answer.txt`
+pub fn answer_path(sample_dir: &Path, day: u32, part: u8) -> PathBuf {
+    sample_dir.join(format!("day{:02}-{}.answer.txt", day, part))
+}
+
+fn decode_entities(html: &str) -> String {
+    html.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Text content of an HTML fragment
+fn text_content(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&text)
+}
+
+/// Contents of the elements delimited by `open` and `close`
+fn elements<'a>(html: &'a str, open: &'a str, close: &'a str) -> impl Iterator<Item = &'a str> {
+    let mut rest = html;
+    std::iter::from_fn(move || {
+        let start = rest.find(open)? + open.len();
+        let len = rest[start..].find(close)?;
+        let content = &rest[start..start + len];
+        rest = &rest[start + len + close.len()..];
+        Some(content)
+    })
+}
+
+/// Extracts the example and expected answer of each part of a puzzle
+///
+/// The example of a part is the first `<pre><code>` block of its article, and
+/// its answer the last emphasised `<code><em>` value. A part without its own
+/// example reuses the one of the previous part.
+pub fn extract_samples(puzzle: &str) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = Vec::new();
+    for (i, article) in elements(puzzle, "<article", "</article>").enumerate() {
+        let input = elements(article, "<pre><code>", "</code></pre>")
+            .next()
+            .map(text_content)
+            .or_else(|| samples.last().map(|s| s.input.clone()));
+        let Some(input) = input else {
+            continue;
+        };
+        let expected = elements(article, "<code><em>", "</em></code>")
+            .last()
+            .map(text_content);
+        samples.push(Sample {
+            part: i as u8 + 1,
+            input,
+            expected,
+        });
+    }
+    samples
+}
+
+/// Writes the fixtures of the samples of a day
+pub fn write_samples(sample_dir: &Path, day: u32, samples: &[Sample]) -> io::Result<Vec<PathBuf>> {
+    if samples.is_empty() {
+        return Ok(Vec::new());
+    }
+    fs::create_dir_all(sample_dir)?;
+    let mut written = Vec::new();
+    for sample in samples {
+        let path = sample_path(sample_dir, day, sample.part);
+        fs::write(&path, &sample.input)?;
+        written.push(path);
+        if let Some(expected) = &sample.expected {
+            let path = answer_path(sample_dir, day, sample.part);
+            fs::write(&path, format!("{}\n", expected))?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// Fills the `__SAMPLE_N__` and `__EXPECTED_N__` placeholders of the template tests
+pub fn fill_template_tests(template: &str, samples: &[Sample]) -> String {
+    let mut rendered = template.to_string();
+    for part in 1..=2 {
+        let sample = samples.iter().find(|s| s.part == part);
+        let sample_placeholder = format!("        __SAMPLE_{}__\n", part);
+        let sample_lines: String = sample
+            .map(|s| {
+                s.input
+                    .lines()
+                    .map(|line| match line {
+                        "" => "\n".to_string(),
+                        line => {
+                            let line = line.replace('\\', "\\\\").replace('"', "\\\"");
+                            format!("        {}\n", line)
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_else(|| "\n".to_string());
+        rendered = rendered.replace(&sample_placeholder, &sample_lines);
+        let expected = sample
+            .and_then(|s| s.expected.as_deref())
+            .filter(|e| e.parse::<u64>().is_ok())
+            .unwrap_or("0");
+        rendered = rendered.replace(&format!("__EXPECTED_{}__", part), expected);
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PUZZLE: &str = r#"<article class="day-desc"><h2>--- Day 3: Gear Ratios ---</h2>
+<p>Here is an example engine schematic:</p>
+<pre><code>467..114..
+...*......
+..35..633.
+</code></pre>
+<p>In this schematic, the sum is <code><em>4361</em></code>.</p>
+</article>
+
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>A gear is any <code>*</code> symbol &amp; its ratio is <code><em>467835</em></code>.</p>
+</article>"#;
+
+    #[test]
+    fn test_extract_samples() {
+        let samples = extract_samples(PUZZLE);
+        let input = "467..114..\n...*......\n..35..633.\n";
+        assert_eq!(
+            samples,
+            vec![
+                Sample {
+                    part: 1,
+                    input: input.to_string(),
+                    expected: Some("4361".to_string()),
+                },
+                Sample {
+                    part: 2,
+                    input: input.to_string(),
+                    expected: Some("467835".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_samples_decodes_html() {
+        let puzzle = "<article><pre><code>a &lt;<em>b</em>&gt; &amp; c\n</code></pre>\
+            <code><em>&quot;x&quot;</em></code></article>";
+        let samples = extract_samples(puzzle);
+        assert_eq!(samples[0].input, "a <b> & c\n");
+        assert_eq!(samples[0].expected.as_deref(), Some("\"x\""));
+    }
+
+    #[test]
+    fn test_fill_template_tests() {
+        let template = "let input = sample_input! {\"\n        __SAMPLE_1__\n        \"};\nlet expected = __EXPECTED_1__;\n\
+            let input = sample_input! {\"\n        __SAMPLE_2__\n        \"};\nlet expected = __EXPECTED_2__;\n";
+        let rendered = fill_template_tests(template, &extract_samples(PUZZLE)[..1]);
+        assert_eq!(
+            rendered,
+            "let input = sample_input! {\"\n        467..114..\n        ...*......\n        ..35..633.\n        \"};\nlet expected = 4361;\n\
+            let input = sample_input! {\"\n\n        \"};\nlet expected = 0;\n"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+
+use crate::{client, samples};
+
+const DAY_PLACEHOLDER: &str = "__DAY__";
+const TITLE_PLACEHOLDER: &str = "__TITLE__";
+/// Marks the registry in `lib.rs`, new days are added before its closing bracket
+const REGISTRY_START: &str = "pub static DAYS: &[&dyn Solution] = &[";
+
+/// Error raised when scaffolding a new day
+#[derive(Debug)]
+pub enum ScaffoldError {
+    AlreadyExists(PathBuf),
+    MissingRegistry(PathBuf),
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+            Self::MissingRegistry(path) => {
+                write!(f, "unable to find the day registry in {}", path.display())
+            }
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|source| ScaffoldError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn write(path: &Path, content: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, content).map_err(|source| ScaffoldError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Files touched while scaffolding a day
+#[derive(Debug)]
+pub struct Scaffold {
+    pub module: PathBuf,
+    pub lib: PathBuf,
+    /// Input placeholder, if there was no input yet
+    pub input: Option<PathBuf>,
+    /// Fixtures extracted from the puzzle examples
+    pub samples: Vec<PathBuf>,
+}
+
+/// Title of a downloaded puzzle, from its `--- Day 5: Title ---` heading
+pub fn puzzle_title(puzzle: &str) -> Option<&str> {
+    let (_, heading) = puzzle.split_once("<h2>--- Day ")?;
+    let (_, rest) = heading.split_once(": ")?;
+    let (title, _) = rest.split_once(" ---</h2>")?;
+    Some(title)
+}
+
+pub fn render_template(template: &str, day: u32, title: &str) -> String {
+    template
+        .replace(DAY_PLACEHOLDER, &day.to_string())
+        .replace(TITLE_PLACEHOLDER, &title.replace('"', "\\\""))
+}
+
+fn module_name(declaration: &str) -> Option<&str> {
+    declaration.strip_prefix("pub mod ")?.strip_suffix(';')
+}
+
+/// Declares the module of a day in `lib.rs` and adds it to the registry
+pub fn register_day(lib: &str, day: u32) -> Option<String> {
+    let module = format!("day{}", day);
+    let mut lines = lib.lines().map(str::to_string).collect_vec();
+
+    // Module declarations are kept sorted like rustfmt does
+    let declaration = format!("pub mod {};", module);
+    let day_modules = lines
+        .iter()
+        .positions(|l| module_name(l).is_some_and(|m| m.starts_with("day")))
+        .collect_vec();
+    let insert_at = day_modules
+        .iter()
+        .copied()
+        .find(|i| module_name(&lines[*i]).is_some_and(|m| m > module.as_str()))
+        .or(day_modules.last().map(|i| i + 1))?;
+    lines.insert(insert_at, declaration);
+
+    // Registry entries are sorted by day number
+    let registry_start = lines.iter().position(|l| l == REGISTRY_START)?;
+    let registry_end = registry_start + lines[registry_start..].iter().position(|l| l == "];")?;
+    let entry_day = |line: &str| -> Option<u32> {
+        line.trim()
+            .strip_prefix("&day")?
+            .split_once("::")?
+            .0
+            .parse()
+            .ok()
+    };
+    let insert_at = (registry_start + 1..registry_end)
+        .find(|i| entry_day(&lines[*i]).is_some_and(|d| d > day))
+        .unwrap_or(registry_end);
+    lines.insert(insert_at, format!("    &{}::Day{},", module, day));
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// Creates the module of a new day from `template.rs`, and registers it
+pub fn new_day(root: &Path, input_dir: &Path, day: u32) -> Result<Scaffold, ScaffoldError> {
+    let module = root.join("src").join(format!("day{}.rs", day));
+    if module.exists() {
+        return Err(ScaffoldError::AlreadyExists(module));
+    }
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = read(&lib_path)?;
+    if lib.lines().any(|l| l == format!("pub mod day{};", day)) {
+        return Err(ScaffoldError::AlreadyExists(lib_path));
+    }
+    let lib =
+        register_day(&lib, day).ok_or_else(|| ScaffoldError::MissingRegistry(lib_path.clone()))?;
+
+    let puzzle = fs::read_to_string(client::puzzle_path(&root.join("puzzles"), day)).ok();
+    let title = puzzle.as_deref().and_then(puzzle_title).unwrap_or_default();
+    let samples = puzzle
+        .as_deref()
+        .map(samples::extract_samples)
+        .unwrap_or_default();
+    let template = read(&root.join("template.rs"))?;
+    let rendered = render_template(&template, day, title);
+    write(&module, &samples::fill_template_tests(&rendered, &samples))?;
+    write(&lib_path, &lib)?;
+
+    let sample_dir = samples::sample_dir(input_dir);
+    let samples =
+        samples::write_samples(&sample_dir, day, &samples).map_err(|source| ScaffoldError::Io {
+            path: sample_dir,
+            source,
+        })?;
+
+    let input_path = input_dir.join(format!("day{:02}.txt", day));
+    let input = if input_path.exists() {
+        None
+    } else {
+        fs::create_dir_all(input_dir).map_err(|source| ScaffoldError::Io {
+            path: input_dir.to_path_buf(),
+            source,
+        })?;
+        write(&input_path, "")?;
+        Some(input_path)
+    };
+
+    Ok(Scaffold {
+        module,
+        lib: lib_path,
+        input,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        client::{test::StubServer, Client, FetchStatus},
+        utils::test::temp_dir,
+    };
+
+    /// Registry of a crate with days 1, 2 and 10 solved
+    const LIB: &str = "\
+pub mod solution;
+
+pub mod day1;
+pub mod day10;
+pub mod day2;
+
+use solution::Solution;
+
+pub static DAYS: &[&dyn Solution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day10::Day10,
+];
+";
+
+    fn setup_root(name: &str) -> PathBuf {
+        let root = temp_dir(name);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("template.rs"), include_str!("../template.rs")).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = setup_root("scaffold");
+        fs::create_dir_all(root.join("puzzles")).unwrap();
+        fs::write(
+            root.join("puzzles/day11.html"),
+            "<article class=\"day-desc\"><h2>--- Day 11: Cosmic Expansion ---</h2>\
+            <pre><code>#..\n..#\n</code></pre><code><em>4</em></code></article>",
+        )
+        .unwrap();
+        let scaffold = new_day(&root, &root.join("inputs"), 11).unwrap();
+
+        let module = fs::read_to_string(scaffold.module).unwrap();
+        assert!(module.contains("const DAY: u32 = 11;"));
+        assert!(module.contains("impl Day for Day11 {"));
+        assert!(module.contains("\"Cosmic Expansion\""));
+        assert!(
+            module.contains("        #..\n        ..#\n        \"};\n        let expected = 4;")
+        );
+        assert!(!module.contains("__"));
+
+        let lib = fs::read_to_string(scaffold.lib).unwrap();
+        assert!(lib.contains("pub mod day10;\npub mod day11;\npub mod day2;"));
+        assert!(lib.contains("    &day10::Day10,\n    &day11::Day11,\n];"));
+        assert_eq!(scaffold.input, Some(root.join("inputs/day11.txt")));
+        assert_eq!(
+            fs::read_to_string(root.join("inputs/samples/day11-1.txt")).unwrap(),
+            "#..\n..#\n"
+        );
+        assert_eq!(scaffold.samples.len(), 2);
+
+        let error = new_day(&root, &root.join("inputs"), 11).unwrap_err();
+        assert!(matches!(error, ScaffoldError::AlreadyExists(_)));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_new_day_refuses_registered_day() {
+        let root = setup_root("scaffold-registered");
+        let error = new_day(&root, &root.join("inputs"), 2).unwrap_err();
+        assert!(matches!(error, ScaffoldError::AlreadyExists(path) if path.ends_with("lib.rs")));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_after_new_day() {
+        let server = StubServer::start(|request| match request.path.as_str() {
+            "/2023/day/11/input" => (200, "#..\n".to_string()),
+            _ => (200, "<article></article>".to_string()),
+        });
+        let client = Client::new(server.url.as_str(), "token").with_min_interval(Duration::ZERO);
+        let root = setup_root("scaffold-fetch");
+        let inputs = root.join("inputs");
+        let scaffold = new_day(&root, &inputs, 11).unwrap();
+        assert_eq!(fs::read_to_string(scaffold.input.unwrap()).unwrap(), "");
+
+        let report = client::fetch_day(&client, 11, &inputs, &root.join("puzzles")).unwrap();
+        assert_eq!(report.input.1, FetchStatus::Downloaded);
+        assert_eq!(
+            fs::read_to_string(inputs.join("day11.txt")).unwrap(),
+            "#..\n"
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
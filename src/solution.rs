@@ -0,0 +1,219 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use num_bigint::BigInt;
+
+use crate::{
+    bench::{self, BenchConfig, PartBench},
+    memory,
+    utils::ParseError,
+};
+
+/// Answer of a puzzle part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Big(BigInt),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(n) => n.fmt(f),
+            Self::Signed(n) => n.fmt(f),
+            Self::Big(n) => n.fmt(f),
+            Self::Text(s) => s.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_answer_from {
+    ($variant:ident: $($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from!(Unsigned: u8, u16, u32, u64);
+impl_answer_from!(Signed: i8, i16, i32, i64);
+impl_answer_from!(Big: BigInt);
+impl_answer_from!(Text: String, &str);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::Unsigned(value as u64)
+    }
+}
+
+/// Error raised when a part can't be solved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The input doesn't have the expected format
+    Parse(ParseError),
+    /// The input is well-formed but has no answer, e.g. a walk that never ends
+    NoAnswer { day: u32, reason: String },
+    /// Only parts 1 and 2 exist
+    InvalidPart(u8),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::NoAnswer { day, reason } => write!(f, "day{}: no answer, {}", day, reason),
+            Self::InvalidPart(part) => write!(f, "invalid part {}, expected 1 or 2", part),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::NoAnswer { .. } | Self::InvalidPart(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// A solved day of the calendar, parsing its input once for both parts
+pub trait Day: Sync {
+    /// Input of the parts, which may borrow from the raw input
+    type Parsed<'a>;
+
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError>;
+    fn part1(&self, parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError>;
+    fn part2(&self, parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError>;
+
+    /// Runs the given part (1 or 2)
+    fn part(&self, part: u8, parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        match part {
+            1 => self.part1(parsed),
+            2 => self.part2(parsed),
+            _ => Err(SolveError::InvalidPart(part)),
+        }
+    }
+}
+
+/// Answer of a part, with the resources it took once the input was parsed
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub part: u8,
+    pub answer: Result<Answer, SolveError>,
+    pub solve: Duration,
+    /// Peak bytes allocated, parsed input included, with the `alloc-stats` feature
+    pub peak_bytes: Option<usize>,
+}
+
+/// Parts run on a single parsing of the input
+#[derive(Debug, Clone)]
+pub struct DayRun {
+    pub parse: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+/// Object-safe view of a [`Day`], as stored in the registry
+pub trait Solution: Sync {
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+
+    /// Parses the input once, then runs the given parts on it
+    fn run(&self, input: &str, parts: &[u8]) -> Result<DayRun, ParseError>;
+
+    /// Times the parsing of the input and the given part apart
+    fn bench(&self, input: &str, part: u8, config: &BenchConfig) -> Result<PartBench, SolveError>;
+
+    /// Runs the given part (1 or 2)
+    fn part(&self, part: u8, input: &str) -> Result<Answer, SolveError> {
+        let mut run = self.run(input, &[part])?;
+        run.parts.remove(0).answer
+    }
+}
+
+impl<D: Day> Solution for D {
+    fn day(&self) -> u32 {
+        Day::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Day::title(self)
+    }
+
+    fn run(&self, input: &str, parts: &[u8]) -> Result<DayRun, ParseError> {
+        let before = memory::allocated();
+        let start = Instant::now();
+        let (parsed, parse_peak) = memory::peak_allocated(|| self.parse(input));
+        let parse = start.elapsed();
+        let parsed = parsed?;
+        let retained = before
+            .zip(memory::allocated())
+            .map(|(b, a)| a.saturating_sub(b));
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let (answer, peak) = memory::peak_allocated(|| self.part(part, &parsed));
+                let solve = start.elapsed();
+                let peak_bytes = peak
+                    .zip(retained)
+                    .zip(parse_peak)
+                    .map(|((peak, retained), parse_peak)| (retained + peak).max(parse_peak));
+                PartRun {
+                    part,
+                    answer,
+                    solve,
+                    peak_bytes,
+                }
+            })
+            .collect();
+        Ok(DayRun { parse, parts })
+    }
+
+    fn bench(&self, input: &str, part: u8, config: &BenchConfig) -> Result<PartBench, SolveError> {
+        let parsed = self.parse(input)?;
+        self.part(part, &parsed)?;
+        Ok(PartBench {
+            day: Day::day(self),
+            part,
+            parse: bench::measure(config, || self.parse(black_box(input))),
+            solve: bench::measure(config, || self.part(part, black_box(&parsed))),
+            total: bench::measure(config, || {
+                self.parse(black_box(input))
+                    .map_err(SolveError::from)
+                    .and_then(|parsed| self.part(part, &parsed))
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_invalid_part() {
+        let day = crate::get_day(1).unwrap();
+        assert_eq!(day.part(3, "1abc2\n"), Err(SolveError::InvalidPart(3)));
+        let run = day.run("1abc2\n", &[1, 0]).unwrap();
+        assert_eq!(run.parts[0].answer, Ok(Answer::Unsigned(12)));
+        assert_eq!(run.parts[1].answer, Err(SolveError::InvalidPart(0)));
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::{
+    answers::AnswerStore,
+    client::{Client, ClientError, SubmitOutcome},
+};
+
+/// Error raised when submitting an answer
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The answer was already refused by the website
+    AlreadyRejected(String),
+    Client(ClientError),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRejected(answer) => {
+                write!(
+                    f,
+                    "{} was already submitted and is not the right answer",
+                    answer
+                )
+            }
+            Self::Client(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<ClientError> for SubmitError {
+    fn from(e: ClientError) -> Self {
+        Self::Client(e)
+    }
+}
+
+/// Submits an answer, unless its outcome is already known from the store
+///
+/// Correct answers are recorded in the store and wrong ones remembered, so that
+/// they are never submitted twice. The store is not saved.
+pub fn submit(
+    client: &Client,
+    store: &mut AnswerStore,
+    day: u32,
+    part: u8,
+    answer: &str,
+) -> Result<SubmitOutcome, SubmitError> {
+    if store.get(day, part) == Some(answer) {
+        return Ok(SubmitOutcome::AlreadySolved);
+    }
+    if store.is_rejected(day, part, answer) {
+        return Err(SubmitError::AlreadyRejected(answer.to_string()));
+    }
+    let outcome = client.submit_answer(day, part, answer)?;
+    match outcome {
+        SubmitOutcome::Correct => {
+            store.record(day, part, answer);
+        }
+        SubmitOutcome::TooHigh | SubmitOutcome::TooLow | SubmitOutcome::Wrong => {
+            store.reject(day, part, answer);
+        }
+        _ => {}
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use super::*;
+    use crate::{client::test::StubServer, utils::test::temp_dir};
+
+    #[test]
+    fn test_submit() {
+        let server = StubServer::start(|request| {
+            let text = if request.body.ends_with("answer=142") {
+                "That's the right answer!"
+            } else {
+                "That's not the right answer; your answer is too low."
+            };
+            (200, format!("<article><p>{}</p></article>", text))
+        });
+        let client = Client::new(server.url.as_str(), "token").with_min_interval(Duration::ZERO);
+        let dir = temp_dir("submit");
+        let mut store = AnswerStore::load(dir.join("answers.toml")).unwrap();
+
+        let outcome = submit(&client, &mut store, 1, 1, "100").unwrap();
+        assert_eq!(outcome, SubmitOutcome::TooLow);
+        assert!(store.is_rejected(1, 1, "100"));
+        let error = submit(&client, &mut store, 1, 1, "100").unwrap_err();
+        assert!(matches!(error, SubmitError::AlreadyRejected(_)));
+
+        let outcome = submit(&client, &mut store, 1, 1, "142").unwrap();
+        assert_eq!(outcome, SubmitOutcome::Correct);
+        assert_eq!(store.get(1, 1), Some("142"));
+        let outcome = submit(&client, &mut store, 1, 1, "142").unwrap();
+        assert_eq!(outcome, SubmitOutcome::AlreadySolved);
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2023/day/1/answer");
+        assert_eq!(requests[0].body, "level=1&answer=100");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,19 +1,97 @@
-#[macro_export]
-macro_rules! get_day_input {
-    () => {{
-        let filepath = file!();
-        let day: u32 = filepath
-            .strip_prefix("src/days/day")
-            .and_then(|s| s.strip_suffix(".rs"))
-            .and_then(|s| s.parse().ok())
-            .expect("unable to parse the day");
-        let path = format!("../inputs/day{:02}.txt", day);
-        let data = std::fs::read_to_string(path).expect("Could not open file");
-        data
-    }};
+use std::{fmt, str::FromStr};
+
+pub mod geom;
+mod grid;
+mod input;
+pub mod intervals;
+pub mod math;
+pub mod piecewise;
+
+pub use geom::Pos;
+pub use grid::Grid;
+pub use input::{default_input_dir, input_dir, InputError, InputSource, INPUT_DIR_VAR};
+
+/// Error raised when a puzzle input doesn't have the expected format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number, when the error can be pinned to a token
+    pub column: Option<usize>,
+    /// Description of what the parser was expecting
+    pub expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day{} line {}", self.day, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+        write!(f, ": expected {}", self.expected)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line of a puzzle input, carrying enough context to report parse errors
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub day: u32,
+    /// 1-based line number
+    pub number: usize,
+    pub text: &'a str,
 }
 
-pub use get_day_input;
+impl<'a> Line<'a> {
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            day: self.day,
+            line: self.number,
+            column: None,
+            expected: expected.into(),
+        }
+    }
+
+    /// Error pointing at `token`, which must be a subslice of the line
+    pub fn error_at(&self, token: &str, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            column: Some(self.column_of(token)),
+            ..self.error(expected)
+        }
+    }
+
+    /// 1-based column of `token`, which must be a subslice of the line
+    fn column_of(&self, token: &str) -> usize {
+        let offset = (token.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+        self.text[..offset.min(self.text.len())].chars().count() + 1
+    }
+
+    /// Parses `token`, a subslice of the line, reporting its column on failure
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error_at(token, expected))
+    }
+}
+
+/// Iterates over the lines of a puzzle input with their line numbers
+pub fn lines(day: u32, input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(move |(i, text)| Line {
+        day,
+        number: i + 1,
+        text,
+    })
+}
+
+/// Error for an input that ended before the parser was done
+pub fn unexpected_end(day: u32, input: &str, expected: impl Into<String>) -> ParseError {
+    ParseError {
+        day,
+        line: input.lines().count() + 1,
+        column: None,
+        expected: expected.into(),
+    }
+}
 
 #[macro_export]
 macro_rules! sample_input {
@@ -25,3 +103,39 @@ macro_rules! sample_input {
 
 #[cfg(test)]
 pub use sample_input;
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::{env, fs, path::PathBuf};
+
+    /// Compares `actual` with the golden file `tests/golden/<name>`
+    ///
+    /// Run the tests with `UPDATE_GOLDEN=1` to rewrite the golden files.
+    pub fn assert_golden(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(name);
+        if env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+        assert!(
+            expected == actual,
+            "{} is outdated, run with UPDATE_GOLDEN=1 to update it\n--- expected\n{}\n--- actual\n{}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+
+    /// Fresh temporary directory for a test
+    pub fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc23-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// Position on a grid, from the top left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Signed offset between two positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vec2 {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Pos {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Position at an offset, unless it is left or above the origin
+    pub fn offset(self, offset: Vec2) -> Option<Self> {
+        Some(Self {
+            row: self.row.checked_add_signed(offset.row)?,
+            col: self.col.checked_add_signed(offset.col)?,
+        })
+    }
+
+    /// Neighbouring position in a direction
+    pub fn step(self, direction: impl Into<Vec2>) -> Option<Self> {
+        self.offset(direction.into())
+    }
+
+    /// Offset leading from this position to `other`
+    pub fn delta(self, other: Self) -> Vec2 {
+        Vec2::new(
+            other.row as isize - self.row as isize,
+            other.col as isize - self.col as isize,
+        )
+    }
+
+    pub fn manhattan(self, other: Self) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+impl Vec2 {
+    pub const fn new(row: isize, col: isize) -> Self {
+        Self { row, col }
+    }
+
+    pub fn manhattan(self) -> usize {
+        self.row.unsigned_abs() + self.col.unsigned_abs()
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<isize> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, factor: isize) -> Self {
+        Self::new(self.row * factor, self.col * factor)
+    }
+}
+
+/// Side to turn towards, parsed from `L` or `R`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Left => "L",
+            Self::Right => "R",
+        })
+    }
+}
+
+impl FromStr for Turn {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "L" => Ok(Self::Left),
+            "R" => Ok(Self::Right),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Cardinal direction, with the north towards the first row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise from the north
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 4]
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => Self::from_index(self as usize + 3),
+            Turn::Right => Self::from_index(self as usize + 1),
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn(Turn::Left)
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.turn(Turn::Right)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self as usize + 2)
+    }
+}
+
+impl From<Direction> for Vec2 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Vec2::new(-1, 0),
+            Direction::East => Vec2::new(0, 1),
+            Direction::South => Vec2::new(1, 0),
+            Direction::West => Vec2::new(0, -1),
+        }
+    }
+}
+
+/// Cardinal or diagonal direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Clockwise from the north
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 8]
+    }
+
+    /// Turns by 45 degrees
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => Self::from_index(self as usize + 7),
+            Turn::Right => Self::from_index(self as usize + 1),
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self as usize + 4)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        Self::from_index(direction as usize * 2)
+    }
+}
+
+impl From<Direction8> for Vec2 {
+    fn from(direction: Direction8) -> Self {
+        let (row, col) = match direction {
+            Direction8::North => (-1, 0),
+            Direction8::NorthEast => (-1, 1),
+            Direction8::East => (0, 1),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (1, 0),
+            Direction8::SouthWest => (1, -1),
+            Direction8::West => (0, -1),
+            Direction8::NorthWest => (-1, -1),
+        };
+        Vec2::new(row, col)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::West.turn_right(), Direction::North);
+        assert_eq!(Direction::East.reverse(), Direction::West);
+        assert_eq!(Direction8::North.turn(Turn::Left), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthWest.reverse(), Direction8::NorthEast);
+        for direction in Direction::ALL {
+            assert_eq!(
+                Vec2::from(direction),
+                Vec2::from(Direction8::from(direction))
+            );
+            assert_eq!(Vec2::from(direction.reverse()), -Vec2::from(direction));
+        }
+    }
+
+    #[test]
+    fn test_positions() {
+        let pos = Pos::new(2, 3);
+        assert_eq!(pos.step(Direction::North), Some(Pos::new(1, 3)));
+        assert_eq!(Pos::new(0, 3).step(Direction8::NorthEast), None);
+        assert_eq!(pos.offset(Vec2::from(Direction::West) * 4), None);
+        assert_eq!(pos.delta(Pos::new(0, 5)), Vec2::new(-2, 2));
+        assert_eq!(pos.manhattan(Pos::new(0, 5)), 4);
+        assert_eq!(pos.delta(Pos::new(0, 5)).manhattan(), 4);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use super::{
+    geom::{Direction, Direction8, Pos, Vec2},
+    lines, unexpected_end, Line, ParseError,
+};
+
+/// Rectangular grid of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Parses a grid with one cell per character, failing on ragged rows
+    ///
+    /// `cell` receives the line and the character to parse, as a subslice of
+    /// the line so that errors can point at it.
+    pub fn parse<F>(day: u32, input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(&Line<'_>, &str) -> Result<T, ParseError>,
+    {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for line in lines(day, input) {
+            let before = cells.len();
+            for (i, c) in line.text.char_indices() {
+                cells.push(cell(&line, &line.text[i..i + c.len_utf8()])?);
+            }
+            let row_width = cells.len() - before;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(line.error(format!("a row of {} cells", width)))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(unexpected_end(day, input, "a grid of at least one cell")),
+        }
+    }
+
+    /// Parses a grid of cells implementing `FromStr`
+    pub fn parse_cells(day: u32, input: &str, expected: &str) -> Result<Self, ParseError>
+    where
+        T: FromStr,
+    {
+        Self::parse(day, input, |line, cell| line.parse(cell, expected))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.row * self.width + pos.col])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.row * self.width + pos.col])
+    }
+
+    /// Position at an offset from `pos`, if it is within the grid
+    pub fn offset(&self, pos: Pos, offset: Vec2) -> Option<Pos> {
+        pos.offset(offset).filter(|pos| self.contains(*pos))
+    }
+
+    /// Neighbour of a cell in a direction, if it is within the grid
+    pub fn step(&self, pos: Pos, direction: impl Into<Vec2>) -> Option<Pos> {
+        self.offset(pos, direction.into())
+    }
+
+    /// Orthogonal neighbours of a cell within the grid, clockwise from the north
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// Orthogonal and diagonal neighbours of a cell within the grid
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// Positions of every cell, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Pos::new(i / width, i % width))
+    }
+
+    /// Cells with their positions, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Rows of the grid, which are empty slices when the grid has no columns
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        // Without columns there are no cells, `max` only avoids a zero step
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    /// Position of the first cell matching `predicate`, row by row
+    pub fn find(&self, predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| Pos::new(i / self.width, i % self.width))
+    }
+
+    /// Grid of the same size with each cell mapped
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is out of the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is out of the grid", pos))
+    }
+}
+
+/// Displays the cells back as text, one row per line
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                cell.fmt(f)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY: u32 = 0;
+
+    #[test]
+    fn test_parse_and_display() {
+        let input = "123\n456\n";
+        let grid = Grid::<u8>::parse_cells(DAY, input, "a digit").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 2)], 6);
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.to_string(), input);
+        assert_eq!(grid.find(|d| *d > 4), Some(Pos::new(1, 1)));
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.rows().nth(1), Some(&[4, 5, 6][..]));
+
+        let error = Grid::<u8>::parse_cells(DAY, "12\n3x\n", "a digit").unwrap_err();
+        assert_eq!(error.to_string(), "day0 line 2, column 2: expected a digit");
+        let error = Grid::<u8>::parse_cells(DAY, "12\n3\n", "a digit").unwrap_err();
+        assert_eq!(error.to_string(), "day0 line 2: expected a row of 2 cells");
+        assert!(Grid::<u8>::parse_cells(DAY, "", "a digit").is_err());
+    }
+
+    #[test]
+    fn test_zero_width() {
+        let grid = Grid::new(0, 2, 0u8);
+        assert_eq!(grid.rows().map(<[u8]>::len).collect::<Vec<_>>(), vec![0, 0]);
+        assert_eq!(grid.column(0).count(), 0);
+        assert_eq!(grid.positions().count(), 0);
+        assert_eq!(grid.find(|_| true), None);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, ());
+        let corner = grid.neighbours4(Pos::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(corner, vec![Pos::new(0, 1), Pos::new(1, 0)]);
+        assert_eq!(grid.neighbours8(Pos::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8(Pos::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours4(Pos::new(2, 1)).count(), 3);
+        assert_eq!(grid.step(Pos::new(2, 2), Direction::East), None);
+        assert_eq!(
+            grid.offset(Pos::new(2, 2), Vec2::new(-2, -1)),
+            Some(Pos::new(0, 1))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    env, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+
+/// Environment variable overriding the directory containing the puzzle inputs
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Default directory containing the puzzle inputs
+pub fn default_input_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")
+}
+
+/// Directory where new inputs are stored, `$AOC_INPUT_DIR` if set
+pub fn input_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(default_input_dir)
+}
+
+/// Where to read the puzzle input of a day from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Look the input up in `$AOC_INPUT_DIR`, then in the default `inputs/` directory
+    Day(u32),
+    /// Explicit file, e.g. given with `--input <path>`
+    File(PathBuf),
+    /// Standard input, given with `--input -`
+    Stdin,
+}
+
+/// Error raised when a puzzle input can't be read
+#[derive(Debug)]
+pub enum InputError {
+    NotFound { day: u32, tried: Vec<PathBuf> },
+    Io { path: PathBuf, source: io::Error },
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound { day, tried } => {
+                write!(f, "no input found for day {}, tried:", day)?;
+                for path in tried {
+                    write!(f, "\n  - {}", path.display())?;
+                }
+                Ok(())
+            }
+            Self::Io { path, source } => write!(f, "unable to read {}: {}", path.display(), source),
+            Self::Stdin(source) => write!(f, "unable to read stdin: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NotFound { .. } => None,
+            Self::Io { source, .. } | Self::Stdin(source) => Some(source),
+        }
+    }
+}
+
+impl InputSource {
+    /// Source selected by an optional `--input` argument for the given day
+    pub fn from_arg(day: u32, arg: Option<&str>) -> Self {
+        match arg {
+            None => Self::Day(day),
+            Some("-") => Self::Stdin,
+            Some(path) => Self::File(PathBuf::from(path)),
+        }
+    }
+
+    /// Paths looked up for a day, in order of priority
+    pub fn candidates(day: u32) -> Vec<PathBuf> {
+        let dirs = env::var_os(INPUT_DIR_VAR)
+            .map(PathBuf::from)
+            .into_iter()
+            .chain([default_input_dir()]);
+        // `newday.sh` used to save unpadded file names
+        let names = [format!("day{:02}.txt", day), format!("day{}.txt", day)];
+        dirs.flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+            .dedup()
+            .collect()
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        match self {
+            Self::Day(day) => {
+                let tried = Self::candidates(*day);
+                match tried.iter().find(|path| path.is_file()) {
+                    Some(path) => read_file(path),
+                    None => Err(InputError::NotFound { day: *day, tried }),
+                }
+            }
+            Self::File(path) => read_file(path),
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(InputError::Stdin)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(InputSource::from_arg(5, None), InputSource::Day(5));
+        assert_eq!(InputSource::from_arg(5, Some("-")), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg(5, Some("day5.txt")),
+            InputSource::File(PathBuf::from("day5.txt"))
+        );
+    }
+
+    #[test]
+    fn test_not_found_lists_tried_paths() {
+        let error = InputSource::Day(26).read().unwrap_err();
+        let message = error.to_string();
+        assert!(message.starts_with("no input found for day 26, tried:"));
+        for path in InputSource::candidates(26) {
+            assert!(message.contains(&path.display().to_string()));
+        }
+        assert!(message.contains("day26.txt"));
+    }
+}
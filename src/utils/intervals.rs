@@ -0,0 +1,341 @@
+use std::fmt;
+
+use num::PrimInt;
+
+/// Half-open interval `[start, end)` of integers
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// Interval from `start` up to `end` excluded, empty if `end <= start`
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// Interval of `len` values from `start`, `None` if it overflows `T`
+    pub fn with_len(start: T, len: T) -> Option<Self> {
+        Some(Self::new(start, start.checked_add(&len)?))
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Common part of both intervals, `None` if they don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// Translates the interval so that `from` would land on `to`
+    ///
+    /// Panics if the result doesn't fit in `T`.
+    pub fn shift(&self, from: T, to: T) -> Self {
+        Self {
+            start: shift(self.start, from, to),
+            end: shift(self.end, from, to),
+        }
+    }
+}
+
+/// Translates a value so that `from` would land on `to`
+pub(super) fn shift<T: PrimInt>(value: T, from: T, to: T) -> T {
+    if to >= from {
+        value + (to - from)
+    } else {
+        value - (from - to)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:?}, {:?})", self.start, self.end)
+    }
+}
+
+/// Set of integers stored as sorted, disjoint and non-adjacent intervals
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Builds the set from any intervals, sorting and merging them
+    fn normalise(mut unsorted: Vec<Interval<T>>) -> Self {
+        unsorted.sort_by_key(|interval| interval.start);
+        let mut intervals: Vec<Interval<T>> = Vec::with_capacity(unsorted.len());
+        for interval in unsorted.into_iter().filter(|interval| !interval.is_empty()) {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+        Self::from_normalised(intervals)
+    }
+
+    /// Builds the set from intervals already sorted and merged
+    fn from_normalised(intervals: Vec<Interval<T>>) -> Self {
+        debug_assert!(intervals.iter().all(|i| !i.is_empty()));
+        debug_assert!(intervals.windows(2).all(|w| w[0].end < w[1].start));
+        Self { intervals }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        *self = self.union(&Self::from(interval));
+    }
+
+    /// Intervals of the set, sorted
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(i).is_some_and(|i| i.contains(value))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalise([&self.intervals[..], &other.intervals[..]].concat())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            intervals.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self::from_normalised(intervals)
+    }
+
+    /// Values of the set that are not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            while let Some(hole) = others.peek() {
+                if hole.end <= start {
+                    others.next();
+                    continue;
+                }
+                if hole.start >= interval.end {
+                    break;
+                }
+                if hole.start > start {
+                    intervals.push(Interval::new(start, hole.start));
+                }
+                start = hole.end;
+                if hole.end > interval.end {
+                    break;
+                }
+                others.next();
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        Self::from_normalised(intervals)
+    }
+
+    /// Values below `at`, and values from `at` onwards
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let mut below = Vec::new();
+        let mut above = Vec::new();
+        for interval in &self.intervals {
+            if interval.end <= at {
+                below.push(*interval);
+            } else if interval.start >= at {
+                above.push(*interval);
+            } else {
+                below.push(Interval::new(interval.start, at));
+                above.push(Interval::new(at, interval.end));
+            }
+        }
+        (Self::from_normalised(below), Self::from_normalised(above))
+    }
+
+    /// Translates every value so that `from` would land on `to`
+    ///
+    /// Panics if a value doesn't fit in `T` once shifted.
+    pub fn shift(&self, from: T, to: T) -> Self {
+        Self::from_normalised(self.intervals.iter().map(|i| i.shift(from, to)).collect())
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        let intervals = if interval.is_empty() {
+            Vec::new()
+        } else {
+            vec![interval]
+        };
+        Self::from_normalised(intervals)
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self::normalise(iter.into_iter().collect())
+    }
+}
+
+impl<T: PrimInt> FromIterator<IntervalSet<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = IntervalSet<T>>>(iter: I) -> Self {
+        iter.into_iter()
+            .flat_map(|set| set.intervals)
+            .collect::<Self>()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.intervals).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet<u32> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    fn values(set: &IntervalSet<u32>) -> BTreeSet<u32> {
+        set.iter().flat_map(|i| i.start..i.end).collect()
+    }
+
+    fn arb_set() -> impl Strategy<Value = IntervalSet<u32>> {
+        prop::collection::vec((0u32..64, 0u32..16), 0..6).prop_map(|intervals| {
+            intervals
+                .into_iter()
+                .map(|(start, len)| Interval::new(start, start + len))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::new(3u64, 7);
+        assert_eq!(interval.len(), 4);
+        assert!(interval.contains(3) && !interval.contains(7));
+        assert_eq!(Interval::new(5u64, 2).len(), 0);
+        assert_eq!(Interval::with_len(u64::MAX, 1), None);
+        assert_eq!(interval.intersection(&Interval::new(7, 9)), None);
+        assert_eq!(
+            interval.intersection(&Interval::new(5, 9)),
+            Some(Interval::new(5, 7))
+        );
+        assert_eq!(interval.shift(3, 10), Interval::new(10, 14));
+        assert_eq!(interval.shift(3, 0), Interval::new(0, 4));
+    }
+
+    #[test]
+    fn test_set() {
+        let a = set(&[(0, 5), (5, 8), (10, 12), (3, 4), (20, 20)]);
+        assert_eq!(a, set(&[(0, 8), (10, 12)]));
+        assert_eq!(a.len(), 10);
+        assert_eq!(a.min(), Some(0));
+        let b = set(&[(6, 11)]);
+        assert_eq!(a.union(&b), set(&[(0, 12)]));
+        assert_eq!(a.intersection(&b), set(&[(6, 8), (10, 11)]));
+        assert_eq!(a.difference(&b), set(&[(0, 6), (11, 12)]));
+        assert_eq!(a.split_at(4), (set(&[(0, 4)]), set(&[(4, 8), (10, 12)])));
+        assert_eq!(a.shift(10, 100), set(&[(90, 98), (100, 102)]));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_union(a in arb_set(), b in arb_set()) {
+            let union = a.union(&b);
+            prop_assert_eq!(values(&union), &values(&a) | &values(&b));
+            prop_assert_eq!(union.len() as usize, values(&union).len());
+        }
+
+        #[test]
+        fn prop_intersection(a in arb_set(), b in arb_set()) {
+            prop_assert_eq!(values(&a.intersection(&b)), &values(&a) & &values(&b));
+        }
+
+        #[test]
+        fn prop_difference(a in arb_set(), b in arb_set()) {
+            prop_assert_eq!(values(&a.difference(&b)), &values(&a) - &values(&b));
+        }
+
+        #[test]
+        fn prop_split_at(a in arb_set(), at in 0u32..96) {
+            let (below, above) = a.split_at(at);
+            prop_assert!(values(&below).iter().all(|v| *v < at));
+            prop_assert!(values(&above).iter().all(|v| *v >= at));
+            prop_assert_eq!(below.union(&above), a);
+        }
+
+        #[test]
+        fn prop_shift(a in arb_set(), from in 0u32..64, delta in 0u32..64) {
+            let shifted = a.shift(from, from + delta);
+            let expected = values(&a).iter().map(|v| v + delta).collect::<BTreeSet<_>>();
+            prop_assert_eq!(values(&shifted), expected);
+            prop_assert_eq!(shifted.shift(from + delta, from), a);
+        }
+
+        #[test]
+        fn prop_contains(a in arb_set()) {
+            for v in 0..100 {
+                prop_assert_eq!(a.contains(v), values(&a).contains(&v));
+            }
+        }
+    }
+}
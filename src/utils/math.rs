@@ -0,0 +1,46 @@
+use num::Integer;
+
+/// Combines `x ≡ a1 (mod m1)` and `x ≡ a2 (mod m2)` into `x ≡ a (mod m)`
+///
+/// The moduli don't need to be coprime. Returns `None` when the congruences
+/// have no common solution, or when a step of the computation overflows an
+/// `i128`.
+pub fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let gcd = m1.extended_gcd(&m2);
+    let diff = a2.checked_sub(a1)?;
+    if diff % gcd.gcd != 0 {
+        return None;
+    }
+    let m2_reduced = m2 / gcd.gcd;
+    let modulus = (m1 / gcd.gcd).checked_mul(m2)?;
+    // `gcd.x * m1 ≡ gcd (mod m2)`, so `k * m1 ≡ diff (mod m2)`
+    let k = (diff / gcd.gcd)
+        .mod_floor(&m2_reduced)
+        .checked_mul(gcd.x.mod_floor(&m2_reduced))?
+        .mod_floor(&m2_reduced);
+    let x = a1.checked_add(m1.checked_mul(k)?)?;
+    Some((x.mod_floor(&modulus), modulus))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((3, 4), (1, 6)), Some((7, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+        assert_eq!(crt((0, 1), (5, 7)), Some((5, 7)));
+        assert_eq!(crt((-1, 4), (0, 4)), None);
+        assert_eq!(crt((1, 1 << 100), (0, (1 << 100) + 1)), None);
+        // The moduli fit, but not the product of the intermediate residues
+        assert_eq!(crt((0, 3), (1 << 100, (1 << 100) + 1)), None);
+        assert_eq!(crt((i128::MIN, 2), (i128::MAX, 3)), None);
+        let (x, m) = [(3, 4), (2, 3), (0, 7)]
+            .into_iter()
+            .try_fold((0, 1), crt)
+            .unwrap();
+        assert_eq!((x, m), (35, 84));
+    }
+}
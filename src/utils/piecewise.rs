@@ -0,0 +1,317 @@
+use num::PrimInt;
+
+use super::intervals::{self, Interval, IntervalSet};
+
+/// Range of values translated onto a range of the same length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Piece<T> {
+    pub source: Interval<T>,
+    pub destination: T,
+}
+
+impl<T: PrimInt> Piece<T> {
+    fn identity(source: Interval<T>) -> Self {
+        Self {
+            source,
+            destination: source.start,
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.destination == self.source.start
+    }
+
+    /// Value `value` is mapped onto, assuming it is within the source
+    pub fn eval(&self, value: T) -> T {
+        intervals::shift(value, self.source.start, self.destination)
+    }
+
+    /// Values the source is mapped onto
+    pub fn image(&self) -> Interval<T> {
+        self.source.shift(self.source.start, self.destination)
+    }
+
+    /// Part of the piece whose source lies within `source`
+    fn restrict(&self, source: &Interval<T>) -> Option<Self> {
+        let source = self.source.intersection(source)?;
+        Some(Self {
+            source,
+            destination: self.eval(source.start),
+        })
+    }
+}
+
+/// Function on integers made of translated pieces, other values mapping to
+/// themselves
+///
+/// Pieces are kept sorted and merged, so equal maps compare equal. The
+/// maximum value of `T` can't be in a half-open source and always maps to
+/// itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PiecewiseMap<T> {
+    pieces: Vec<Piece<T>>,
+}
+
+impl<T: PrimInt> PiecewiseMap<T> {
+    pub fn identity() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// Map made of `pieces`, `None` if their sources overlap or an image
+    /// doesn't fit in `T`
+    pub fn new(pieces: impl IntoIterator<Item = Piece<T>>) -> Option<Self> {
+        let mut pieces = pieces
+            .into_iter()
+            .filter(|piece| !piece.source.is_empty())
+            .collect::<Vec<_>>();
+        pieces.sort_by_key(|piece| piece.source.start);
+        let overlapping = pieces
+            .windows(2)
+            .any(|w| w[0].source.end > w[1].source.start);
+        let overflowing = pieces
+            .iter()
+            .any(|piece| piece.destination.checked_add(&piece.source.len()).is_none());
+        (!overlapping && !overflowing).then(|| Self::normalise(pieces))
+    }
+
+    /// Drops identity pieces and merges contiguous ones, from sorted and
+    /// disjoint pieces
+    fn normalise(sorted: Vec<Piece<T>>) -> Self {
+        let mut pieces: Vec<Piece<T>> = Vec::with_capacity(sorted.len());
+        for piece in sorted {
+            if piece.source.is_empty() || piece.is_identity() {
+                continue;
+            }
+            match pieces.last_mut() {
+                Some(last)
+                    if last.source.end == piece.source.start
+                        && last.image().end == piece.destination =>
+                {
+                    last.source.end = piece.source.end
+                }
+                _ => pieces.push(piece),
+            }
+        }
+        Self { pieces }
+    }
+
+    /// Pieces not mapping values to themselves, sorted by source
+    pub fn pieces(&self) -> &[Piece<T>] {
+        &self.pieces
+    }
+
+    /// Pieces covering every value, including the identity gaps between them
+    fn segments(&self) -> Vec<Piece<T>> {
+        let mut segments = Vec::with_capacity(2 * self.pieces.len() + 1);
+        let mut start = T::min_value();
+        for piece in &self.pieces {
+            segments.push(Piece::identity(Interval::new(start, piece.source.start)));
+            segments.push(*piece);
+            start = piece.source.end;
+        }
+        segments.push(Piece::identity(Interval::new(start, T::max_value())));
+        segments.retain(|segment| !segment.source.is_empty());
+        segments
+    }
+
+    pub fn eval(&self, value: T) -> T {
+        let i = self
+            .pieces
+            .partition_point(|piece| piece.source.end <= value);
+        match self.pieces.get(i) {
+            Some(piece) if piece.source.contains(value) => piece.eval(value),
+            _ => value,
+        }
+    }
+
+    /// Values the set is mapped onto
+    pub fn eval_set(&self, values: &IntervalSet<T>) -> IntervalSet<T> {
+        self.segments()
+            .iter()
+            .map(|segment| {
+                values
+                    .intersection(&segment.source.into())
+                    .shift(segment.source.start, segment.destination)
+            })
+            .collect()
+    }
+
+    /// Values mapped into the set
+    pub fn preimage(&self, values: &IntervalSet<T>) -> IntervalSet<T> {
+        self.segments()
+            .iter()
+            .map(|segment| {
+                values
+                    .intersection(&segment.image().into())
+                    .shift(segment.destination, segment.source.start)
+            })
+            .collect()
+    }
+
+    /// Map applying `self`, then `next`
+    pub fn then(&self, next: &Self) -> Self {
+        let next_segments = next.segments();
+        let mut pieces = Vec::new();
+        for segment in self.segments() {
+            let image = segment.image();
+            for next_segment in &next_segments {
+                if let Some(part) = next_segment.restrict(&image) {
+                    pieces.push(Piece {
+                        source: part.source.shift(segment.destination, segment.source.start),
+                        destination: part.destination,
+                    });
+                }
+            }
+        }
+        pieces.sort_by_key(|piece| piece.source.start);
+        Self::normalise(pieces)
+    }
+
+    /// Inverse map, `None` unless the map is a bijection
+    pub fn invert(&self) -> Option<Self> {
+        let sources = self.pieces.iter().map(|piece| piece.source);
+        let images = self.pieces.iter().map(|piece| piece.image());
+        let sources = sources.collect::<IntervalSet<_>>();
+        let images = images.collect::<IntervalSet<_>>();
+        // Values outside the pieces map to themselves, so the map can only be
+        // inverted when its images cover exactly its sources
+        if images != sources {
+            return None;
+        }
+        Self::new(self.pieces.iter().map(|piece| Piece {
+            source: piece.image(),
+            destination: piece.source.start,
+        }))
+    }
+}
+
+impl<T: PrimInt> Default for PiecewiseMap<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Strategy generating maps of up to 5 pieces over small values
+#[cfg(test)]
+pub(crate) fn arb_map<T>() -> impl proptest::strategy::Strategy<Value = PiecewiseMap<T>>
+where
+    T: PrimInt + std::fmt::Debug,
+{
+    use proptest::prelude::*;
+
+    prop::collection::vec((0u8..64, 0u8..64, 0u8..16), 0..5).prop_map(|pieces| {
+        // Keep the source ranges disjoint, as in the puzzle inputs
+        let mut sources = IntervalSet::new();
+        let pieces = pieces
+            .into_iter()
+            .map(|(destination, start, len)| Piece {
+                source: Interval::new(T::from(start).unwrap(), T::from(start + len).unwrap()),
+                destination: T::from(destination).unwrap(),
+            })
+            .filter(|piece| {
+                let overlaps = !sources.intersection(&piece.source.into()).is_empty();
+                sources.insert(piece.source);
+                !overlaps
+            });
+        PiecewiseMap::new(pieces).unwrap()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn map(pieces: &[(u32, u32, u32)]) -> PiecewiseMap<u32> {
+        PiecewiseMap::new(pieces.iter().map(|&(destination, start, len)| Piece {
+            source: Interval::new(start, start + len),
+            destination,
+        }))
+        .unwrap()
+    }
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet<u32> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_eval() {
+        let seed_to_soil = map(&[(50, 98, 2), (52, 50, 48)]);
+        assert_eq!(seed_to_soil.eval(79), 81);
+        assert_eq!(seed_to_soil.eval(99), 51);
+        assert_eq!(seed_to_soil.eval(13), 13);
+        assert_eq!(seed_to_soil.eval(u32::MAX), u32::MAX);
+        assert_eq!(
+            seed_to_soil.eval_set(&set(&[(45, 55), (97, 100)])),
+            set(&[(45, 57), (99, 100)])
+        );
+        assert_eq!(
+            seed_to_soil.preimage(&set(&[(51, 53)])),
+            set(&[(50, 51), (99, 100)])
+        );
+        assert!(PiecewiseMap::new([Piece {
+            source: Interval::new(0u32, 3),
+            destination: u32::MAX - 1
+        }])
+        .is_none());
+        assert_eq!(map(&[(2, 1, 1), (3, 2, 2), (7, 7, 3)]), map(&[(2, 1, 3)]));
+    }
+
+    #[test]
+    fn test_then_and_invert() {
+        let seed_to_soil = map(&[(50, 98, 2), (52, 50, 48)]);
+        let soil_to_fertilizer = map(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+        let seed_to_fertilizer = seed_to_soil.then(&soil_to_fertilizer);
+        for seed in [0, 14, 50, 51, 79, 98, 99, 120] {
+            assert_eq!(
+                seed_to_fertilizer.eval(seed),
+                soil_to_fertilizer.eval(seed_to_soil.eval(seed))
+            );
+        }
+        let inverse = seed_to_fertilizer.invert().unwrap();
+        assert_eq!(inverse.then(&seed_to_fertilizer), PiecewiseMap::identity());
+        assert_eq!(map(&[(0, 5, 2), (1, 8, 2)]).invert(), None);
+        assert_eq!(map(&[(10, 0, 2)]).invert(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_then(a in arb_map::<u32>(), b in arb_map::<u32>()) {
+            let composed = a.then(&b);
+            for value in 0..100 {
+                prop_assert_eq!(composed.eval(value), b.eval(a.eval(value)));
+            }
+        }
+
+        #[test]
+        fn prop_eval_set_and_preimage(a in arb_map::<u32>(), values in prop::collection::vec(0u32..100, 0..10)) {
+            let values = values.iter().map(|&v| Interval::new(v, v + 1)).collect::<IntervalSet<_>>();
+            let image = (0..200)
+                .filter(|v| values.contains(*v))
+                .map(|v| Interval::new(a.eval(v), a.eval(v) + 1))
+                .collect::<IntervalSet<_>>();
+            prop_assert_eq!(a.eval_set(&values), image);
+            let preimage = (0..200)
+                .filter(|v| values.contains(a.eval(*v)))
+                .map(|v| Interval::new(v, v + 1))
+                .collect::<IntervalSet<_>>();
+            prop_assert_eq!(a.preimage(&values).intersection(&Interval::new(0, 200).into()), preimage);
+        }
+
+        #[test]
+        fn prop_invert(swaps in prop::collection::vec((0u32..32, 0u32..32, 1u32..16), 0..5)) {
+            // Swapping two disjoint ranges is a bijection, and so are compositions of them
+            let bijection = swaps.iter().fold(PiecewiseMap::identity(), |acc, &(a, gap, len)| {
+                let b = a + len + gap;
+                acc.then(&map(&[(b, a, len), (a, b, len)]))
+            });
+            let inverse = bijection.invert().unwrap();
+            prop_assert_eq!(bijection.then(&inverse), PiecewiseMap::identity());
+            prop_assert_eq!(inverse.then(&bijection), PiecewiseMap::identity());
+        }
+    }
+}
@@ -1,15 +1,46 @@
-pub fn part1(input: &str) -> u64 {
+use crate::{
+    solution::{Answer, Day, SolveError},
+    utils::{self, Line, ParseError},
+};
+
+const DAY: u32 = __DAY__;
+
+fn parse_input(input: &str) -> Result<Vec<Line<'_>>, ParseError> {
+    Ok(utils::lines(DAY, input).collect())
+}
+
+fn part1(_lines: &[Line<'_>]) -> u64 {
     0
 }
 
-pub fn part2(input: &str) -> u64 {
+fn part2(_lines: &[Line<'_>]) -> u64 {
     0
 }
 
-fn main() {
-    let input = crate::utils::get_day_input!();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+pub struct Day__DAY__;
+
+impl Day for Day__DAY__ {
+    type Parsed<'a> = Vec<Line<'a>>;
+
+    fn day(&self) -> u32 {
+        DAY
+    }
+
+    fn title(&self) -> &'static str {
+        "__TITLE__"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed<'a>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, lines: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part1(lines).into())
+    }
+
+    fn part2(&self, lines: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(part2(lines).into())
+    }
 }
 
 #[cfg(test)]
@@ -19,33 +50,18 @@ mod test {
     #[test]
     fn test_part1() {
         let input = crate::utils::sample_input! {"
-
+        __SAMPLE_1__
         "};
-        let expected = 0;
-        assert_eq!(part1(&input), expected);
+        let expected = __EXPECTED_1__;
+        assert_eq!(parse_input(input).map(|lines| part1(&lines)), Ok(expected));
     }
 
     #[test]
     fn test_part2() {
         let input = crate::utils::sample_input! {"
-
+        __SAMPLE_2__
         "};
-        let expected = 0;
-        assert_eq!(part2(&input), expected);
-    }
-
-    extern crate test;
-    use test::test::Bencher;
-
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        let input = crate::utils::get_day_input!();
-        b.iter(|| part1(&input));
-    }
-
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        let input = crate::utils::get_day_input!();
-        b.iter(|| part2(&input));
+        let expected = __EXPECTED_2__;
+        assert_eq!(parse_input(input).map(|lines| part2(&lines)), Ok(expected));
     }
 }